@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tusk_lexer::Lexer;
+use tusk_parser::Parser;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(source) = std::str::from_utf8(data) {
+        let _ = Parser::new(Lexer::new(source)).all();
+        let _ = Parser::from_source(source).all();
+    }
+});
@@ -0,0 +1,56 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use tusk_lexer::Lexer;
+use tusk_parser::Parser;
+
+/// Raw bytes rarely get past the lexer, so this target builds its input from
+/// tokens the parser cares about instead.
+#[derive(Arbitrary, Debug)]
+enum Fragment {
+    Variable(u8),
+    Integer(u16),
+    String(u8),
+    Identifier(u8),
+    Keyword(u8),
+    Punctuation(u8),
+}
+
+const KEYWORDS: &[&str] = &[
+    "function", "fn", "class", "interface", "extends", "implements", "new", "static", "public", "protected",
+    "private", "final", "abstract", "readonly", "const", "use", "if", "elseif", "else", "while", "do",
+    "foreach", "as", "return", "break", "continue", "echo", "print", "yield", "from", "list", "array",
+    "isset", "empty", "unset", "exit", "clone", "include", "require", "eval", "global", "declare",
+    "goto", "__halt_compiler", "__LINE__", "true", "false", "null",
+];
+
+const PUNCTUATION: &[&str] = &[
+    "(", ")", "[", "]", "{", "}", ";", ",", "=", "=>", "->", "?->", "::", "+", "-", "*", "/", ".",
+    "!", "~", "@", "&", "|", "^", "<<", ">>", "&&", "||", "<", ">", "<=", ">=", "...", "?", ":",
+    "#[", "$", "<?php", "/** @param int $a */", "// comment",
+];
+
+impl Fragment {
+    fn write(&self, source: &mut String) {
+        match self {
+            Self::Variable(n) => source.push_str(&format!("$v{}", n % 8)),
+            Self::Integer(n) => source.push_str(&n.to_string()),
+            Self::String(n) => source.push_str(&format!("'s{}'", n % 8)),
+            Self::Identifier(n) => source.push_str(&format!("Name{}", n % 8)),
+            Self::Keyword(n) => source.push_str(KEYWORDS[*n as usize % KEYWORDS.len()]),
+            Self::Punctuation(n) => source.push_str(PUNCTUATION[*n as usize % PUNCTUATION.len()]),
+        }
+    }
+}
+
+fuzz_target!(|fragments: Vec<Fragment>| {
+    let mut source = String::new();
+
+    for fragment in &fragments {
+        fragment.write(&mut source);
+        source.push(' ');
+    }
+
+    let _ = Parser::new(Lexer::new(&source)).all();
+});
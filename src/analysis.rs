@@ -0,0 +1,56 @@
+use crate::Class;
+use crate::Constant;
+use crate::Expression;
+use crate::Function;
+use crate::Statement;
+
+/// Finds the constants declared with `const` or `define()`, including those
+/// nested inside control structures such as `if (!defined('FOO')) { ... }`,
+/// inside expressions such as `defined('FOO') || define('FOO', 1)`, and
+/// inside function and method bodies.
+pub fn find_constants(statements: &[Statement]) -> Vec<Constant> {
+    let mut constants = Vec::new();
+
+    for statement in statements {
+        match statement {
+            Statement::Const(declared) => constants.extend(declared.iter().cloned()),
+            Statement::Function(function) => constants.extend(function_constants(function)),
+            Statement::Class(class) => constants.extend(class_constants(class)),
+            _ => {
+                for expression in statement.expressions() {
+                    constants.extend(expression_constants(expression));
+                }
+
+                for block in statement.blocks() {
+                    constants.extend(find_constants(block));
+                }
+            }
+        }
+    }
+
+    constants
+}
+
+fn expression_constants(expression: &Expression) -> Vec<Constant> {
+    let mut constants: Vec<Constant> = expression.as_define().into_iter().collect();
+
+    match expression {
+        Expression::Closure(function) => constants.extend(function_constants(function)),
+        Expression::AnonymousClass(class) => constants.extend(class_constants(class)),
+        _ => (),
+    }
+
+    for child in expression.children() {
+        constants.extend(expression_constants(child));
+    }
+
+    constants
+}
+
+fn function_constants(function: &Function) -> Vec<Constant> {
+    function.body.as_deref().map(find_constants).unwrap_or_default()
+}
+
+fn class_constants(class: &Class) -> Vec<Constant> {
+    class.methods().flat_map(function_constants).collect()
+}
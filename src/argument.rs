@@ -0,0 +1,48 @@
+use crate::Expression;
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Argument {
+    pub name: Option<String>,
+    pub value: Expression,
+}
+
+impl Argument {
+    pub fn new(name: Option<String>, value: Expression) -> Self {
+        Self { name, value }
+    }
+
+    pub fn is_named(&self) -> bool {
+        self.name.is_some()
+    }
+}
+
+impl From<Expression> for Argument {
+    fn from(value: Expression) -> Self {
+        Self::new(None, value)
+    }
+}
+
+/// The arguments of a function or method call. PHP 8.1's first-class callable
+/// syntax, e.g. `strlen(...)`, creates a closure instead of calling.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub enum ArgumentList {
+    Arguments(Vec<Argument>),
+    FirstClassCallable,
+}
+
+impl ArgumentList {
+    pub fn arguments(&self) -> &[Argument] {
+        match self {
+            Self::Arguments(args) => args,
+            Self::FirstClassCallable => &[],
+        }
+    }
+}
+
+impl From<Vec<Argument>> for ArgumentList {
+    fn from(args: Vec<Argument>) -> Self {
+        Self::Arguments(args)
+    }
+}
@@ -0,0 +1,24 @@
+use crate::Expression;
+
+use serde::Serialize;
+
+/// An item in an array literal. Items without an explicit key have no `key`,
+/// rather than the index PHP would assign to them at runtime.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ArrayItem {
+    pub key: Option<Expression>,
+    pub value: Expression,
+    pub by_ref: bool,
+    pub spread: bool,
+}
+
+impl ArrayItem {
+    pub fn new(key: Option<Expression>, value: Expression, by_ref: bool) -> Self {
+        Self { key, value, by_ref, spread: false }
+    }
+
+    /// An unpacked item, e.g. `...$items`.
+    pub fn spread(value: Expression) -> Self {
+        Self { key: None, value, by_ref: false, spread: true }
+    }
+}
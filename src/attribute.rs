@@ -0,0 +1,26 @@
+use crate::Argument;
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Attribute {
+    pub name: String,
+    pub args: Vec<Argument>,
+}
+
+impl Attribute {
+    pub fn new(name: String, args: Vec<Argument>) -> Self {
+        Self { name, args }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AttributeGroup {
+    pub attributes: Vec<Attribute>,
+}
+
+impl AttributeGroup {
+    pub fn new(attributes: Vec<Attribute>) -> Self {
+        Self { attributes }
+    }
+}
@@ -21,11 +21,11 @@ pub enum BinaryOp {
     GreaterThanEquals,
 }
 
-impl From<TokenType> for BinaryOp {
-    fn from(kind: TokenType) -> Self {
+impl BinaryOp {
+    pub fn from_token(kind: TokenType) -> Option<Self> {
         use TokenType::*;
 
-        match kind {
+        Some(match kind {
             Plus => Self::Add,
             Minus => Self::Subtract,
             Asterisk => Self::Multiply,
@@ -42,7 +42,7 @@ impl From<TokenType> for BinaryOp {
             LessThan => Self::LessThan,
             GreaterThanEquals => Self::GreaterThanEquals,
             LessThanEquals => Self::LessThanEquals,
-            _ => unreachable!(),
-        }
+            _ => return None,
+        })
     }
 }
@@ -5,16 +5,21 @@ pub struct BindingPower;
 impl BindingPower {
     pub fn postfix(kind: TokenType) -> Option<(u8, ())> {
         Some(match kind {
-            TokenType::LeftBracket | TokenType::Arrow | TokenType::LeftParen => (19, ()),
+            // binds tighter than any operator, so `-$a->b` negates `$a->b`
+            TokenType::LeftBracket | TokenType::Arrow | TokenType::NullsafeArrow | TokenType::LeftParen => (120, ()),
             _ => return None,
         })
     }
 
     pub fn prefix(kind: TokenType) -> Option<((), u8)> {
         Some(match kind {
-            TokenType::BitwiseNot => ((), 100),
+            // `clone $a->b` clones `$a->b`, but `clone $a + 1` adds to the clone
+            TokenType::Clone => ((), 110),
+            TokenType::BitwiseNot | TokenType::At => ((), 100),
             TokenType::Not => ((), 99),
             TokenType::Minus => ((), 98),
+            // `print $a = 1`, `yield $b = 1` and `include $dir . '/file.php'` take the whole expression
+            TokenType::Yield | TokenType::Print | TokenType::Include | TokenType::IncludeOnce | TokenType::Require | TokenType::RequireOnce => ((), 1),
             _ => return None
         })
     }
@@ -23,7 +28,7 @@ impl BindingPower {
         use TokenType::*;
 
         Some(match kind {
-            Asterisk | Slash => (98, 99),
+            Asterisk | Slash | Percent => (98, 99),
             BitwiseLeftShift | BitwiseRightShift => (96, 97),
             BitwiseAnd => (94, 95),
             BitwiseXor => (92, 93),
@@ -32,7 +37,7 @@ impl BindingPower {
             And => (86, 87),
             Or => (84, 85),
             GreaterThan | LessThan | GreaterThanEquals | LessThanEquals => (7, 8),
-            Equals | DoubleArrow => (1, 2),
+            Equals => (1, 2),
             _ => return None,
         })
     }
@@ -1,27 +1,67 @@
+use crate::AttributeGroup;
+use crate::Function;
+use crate::FunctionParameter;
+use crate::Property;
 use crate::Statement;
 use crate::{Flag, Flaggable};
+use crate::Trivia;
 
 use serde::Serialize;
 
 #[derive(Serialize, Debug, Clone, PartialEq)]
 pub struct Class {
-    name: String,
+    name: Option<String>,
     implements: Vec<String>,
     extends: String,
     body: Vec<Statement>,
     flags: Vec<Flag>,
+    pub attributes: Vec<AttributeGroup>,
+    pub doc_comment: Option<String>,
+    pub trivia: Trivia,
 }
 
 impl Class {
-    pub fn new(name: String, implements: Vec<String>, extends: String, body: Vec<Statement>, flags: Vec<Flag>) -> Self {
+    pub fn new(name: Option<String>, implements: Vec<String>, extends: String, body: Vec<Statement>, flags: Vec<Flag>) -> Self {
         Self {
             name,
             implements,
             extends,
             body,
             flags,
+            attributes: Vec::new(),
+            doc_comment: None,
+            trivia: Trivia::default(),
         }
     }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn body(&self) -> &[Statement] {
+        &self.body
+    }
+
+    pub fn methods(&self) -> impl Iterator<Item = &Function> {
+        self.body.iter().filter_map(|statement| match statement {
+            Statement::Function(function) => Some(function),
+            _ => None,
+        })
+    }
+
+    pub fn properties(&self) -> impl Iterator<Item = &Property> {
+        self.body.iter().filter_map(|statement| match statement {
+            Statement::Property(property) => Some(property),
+            _ => None,
+        })
+    }
+
+    /// The parameters of the constructor that are promoted to properties.
+    pub fn promoted_parameters(&self) -> impl Iterator<Item = &FunctionParameter> {
+        self.methods()
+            .filter(|method| method.name.as_deref().is_some_and(|name| name.eq_ignore_ascii_case("__construct")))
+            .flat_map(|method| method.parameters.iter().filter(|parameter| parameter.is_promoted()))
+    }
 }
 
 impl Flaggable for Class {
@@ -0,0 +1,35 @@
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub enum Comment {
+    Line(String),
+    Block(String),
+    DocBlock(String),
+}
+
+impl Comment {
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Line(text) | Self::Block(text) | Self::DocBlock(text) => text,
+        }
+    }
+
+    pub fn is_doc_block(&self) -> bool {
+        matches!(self, Self::DocBlock(..))
+    }
+}
+
+/// Comments attached to a declaration. Leading comments come directly before
+/// it. Trailing comments follow it on the same line, or follow it when nothing
+/// else does before the end of the enclosing block or file.
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct Trivia {
+    pub leading: Vec<Comment>,
+    pub trailing: Vec<Comment>,
+}
+
+impl Trivia {
+    pub fn is_empty(&self) -> bool {
+        self.leading.is_empty() && self.trailing.is_empty()
+    }
+}
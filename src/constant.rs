@@ -0,0 +1,80 @@
+use crate::AttributeGroup;
+use crate::Expression;
+use crate::Type;
+use crate::{Flag, Flaggable};
+use crate::Trivia;
+
+use serde::Serialize;
+
+/// A single constant. In a top-level `const` statement, the first constant
+/// holds the doc comment and trivia of the whole statement.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Constant {
+    pub name: String,
+    pub value: Expression,
+    pub doc_comment: Option<String>,
+    pub trivia: Trivia,
+}
+
+impl Constant {
+    pub fn new(name: String, value: Expression) -> Self {
+        Self {
+            name,
+            value,
+            doc_comment: None,
+            trivia: Trivia::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ClassConst {
+    pub constants: Vec<Constant>,
+    pub type_hint: Option<Type>,
+    flags: Vec<Flag>,
+    pub attributes: Vec<AttributeGroup>,
+    pub doc_comment: Option<String>,
+    pub trivia: Trivia,
+}
+
+impl ClassConst {
+    /// Any comments already attached to the first constant, as when a `const`
+    /// statement turns out to be inside a class body, move to the class constant.
+    pub fn new(mut constants: Vec<Constant>, type_hint: Option<Type>, flags: Vec<Flag>) -> Self {
+        let (doc_comment, trivia) = match constants.first_mut() {
+            Some(first) => (first.doc_comment.take(), std::mem::take(&mut first.trivia)),
+            None => (None, Trivia::default()),
+        };
+
+        Self {
+            constants,
+            type_hint,
+            flags,
+            attributes: Vec::new(),
+            doc_comment,
+            trivia,
+        }
+    }
+
+    pub fn has_constant(&self, name: &str) -> bool {
+        self.constants.iter().any(|constant| constant.name == name)
+    }
+}
+
+impl Flaggable for ClassConst {
+    fn add_flag(&mut self, flag: Flag) {
+        self.flags.push(flag)
+    }
+
+    fn has_flag(&self, flag: Flag) -> bool {
+        self.flags.contains(&flag)
+    }
+
+    fn has_flags(&self) -> bool {
+        !self.flags.is_empty()
+    }
+
+    fn has_visiblity_flag(&self) -> bool {
+        self.flags.iter().any(|flag| flag.is_visibility_flag())
+    }
+}
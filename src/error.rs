@@ -26,14 +26,44 @@ pub enum ParserError<'pe> {
     CanOnlyHaveFlag(Flag, String),
 
     #[error("Unexpected statement {0:?}.")]
-    UnexpectedStatement(Statement),
+    UnexpectedStatement(Box<Statement>),
     #[error("Unexpected expression {0:?}.")]
-    UnexpectedExpression(Expression),
+    UnexpectedExpression(Box<Expression>),
 
     #[error("The method `{0}` has already been defined.")]
     MethodAlreadyExists(String),
+    #[error("The abstract method `{0}` cannot have a body.")]
+    AbstractMethodWithBody(String),
+    #[error("The interface method `{0}` cannot have a body.")]
+    InterfaceMethodWithBody(String),
+    #[error("The non-abstract method `{0}` must have a body.")]
+    MethodWithoutBody(String),
+    #[error("The class `{0}` contains abstract methods and must be declared abstract.")]
+    ClassMustBeAbstract(String),
     #[error("The property `{0}` has already been defined.")]
     PropertyAlreadyExists(String),
+    #[error("The constant `{0}` has already been defined.")]
+    ConstantAlreadyExists(String),
+    #[error("The readonly property `{0}` must have a type.")]
+    ReadonlyPropertyWithoutType(String),
+    #[error("The promoted property `{0}` can only be declared in a constructor.")]
+    PromotedPropertyOutsideConstructor(String),
+    #[error("The promoted property `{0}` cannot be declared in an abstract or interface constructor.")]
+    PromotedPropertyInAbstractConstructor(String),
+    #[error("The variable `${0}` has already been captured.")]
+    DuplicateClosureUse(String),
+    #[error("The captured variable `${0}` cannot have the same name as a parameter.")]
+    ClosureUseShadowsParameter(String),
+    #[error("The variadic parameter `{0}` must be the last parameter.")]
+    VariadicParameterMustBeLast(String),
+    #[error("The named argument `{0}` has already been passed.")]
+    DuplicateNamedArgument(String),
+    #[error("Positional arguments cannot be used after named arguments.")]
+    PositionalArgumentAfterNamedArgument,
+    #[error("Cannot use an empty list.")]
+    EmptyList,
+    #[error("A list cannot be taken by reference, only the items in it.")]
+    ListByReference,
 
     #[error("Expected token {expected_type:?} ({expected_slice}), got {got_type:?} ({got_slice})")]
     ExpectedToken {
@@ -49,6 +79,9 @@ pub enum ParserError<'pe> {
     #[error("Unexpected end of file.")]
     UnexpectedEndOfFile,
 
+    #[error("Nesting exceeds the maximum depth of {0}.")]
+    MaximumDepthExceeded(usize),
+
     #[error("Unknown parser error.")]
     Unknown,
 }
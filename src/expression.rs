@@ -1,5 +1,11 @@
+use crate::{Argument, ArgumentList};
+use crate::ArrayItem;
 use crate::BinaryOp;
+use crate::Class;
+use crate::Constant;
 use crate::Function;
+use crate::ListItem;
+use crate::MagicConst;
 
 use serde::Serialize;
 use tusk_lexer::TokenType;
@@ -10,57 +16,164 @@ pub enum Expression {
     False,
     Null,
     String(String),
-    Integer(i64),
-    Float(f64),
+    /// Number literals keep their source text, e.g. `0xFF` or `1_000`, next to their value.
+    Integer {
+        value: i64,
+        raw: String,
+    },
+    Float {
+        value: f64,
+        raw: String,
+    },
     Variable(String),
     TypedVariable(String, String),
     Identifier(String),
+    MagicConst(MagicConst),
     Binary(Box<Expression>, BinaryOp, Box<Expression>),
     Assign(Box<Expression>, Box<Expression>),
+    AssignRef(Box<Expression>, Box<Expression>),
     Concat(Box<Expression>, Box<Expression>),
-    Array(Vec<Expression>),
+    Array(Vec<ArrayItem>),
+    /// A destructuring pattern on the left of an assignment or as a `foreach`
+    /// value. Skipped slots are `None`.
+    List(Vec<Option<ListItem>>),
     ArrayAccess(Box<Expression>, Option<Box<Expression>>),
-    ArrayItem { key: Box<Expression>, value: Box<Expression> },
     PropertyAccess(Box<Expression>, Box<Expression>),
+    NullsafePropertyAccess(Box<Expression>, Box<Expression>),
+    AnonymousClass(Box<Class>),
     New {
         class: Box<Expression>,
-        args: Vec<Expression>,
+        args: Vec<Argument>,
     },
     Call {
         target: Box<Expression>,
-        args: Vec<Expression>,
+        args: ArgumentList,
     },
     MethodCall {
         target: Box<Expression>,
         method: Box<Expression>,
-        args: Vec<Expression>
+        args: ArgumentList,
+    },
+    NullsafeMethodCall {
+        target: Box<Expression>,
+        method: Box<Expression>,
+        args: Vec<Argument>
     },
-    Closure(Function),
+    Closure(Box<Function>),
     Unary(Box<Expression>),
     Negate(Box<Expression>),
     BitwiseNot(Box<Expression>),
+    Spread(Box<Expression>),
+    Reference(Box<Expression>),
+    Yield {
+        key: Option<Box<Expression>>,
+        value: Option<Box<Expression>>,
+    },
+    YieldFrom(Box<Expression>),
+    Isset(Vec<Expression>),
+    Empty(Box<Expression>),
+    Exit(Option<Box<Expression>>),
+    Print(Box<Expression>),
+    Clone(Box<Expression>),
+    Include(Box<Expression>),
+    IncludeOnce(Box<Expression>),
+    Require(Box<Expression>),
+    RequireOnce(Box<Expression>),
+    Eval(Box<Expression>),
+    ErrorSuppress(Box<Expression>),
 }
 
 impl Expression {
-    pub fn make_infix(lhs: Expression, operator: &TokenType, rhs: Expression) -> Self {
+    /// Combines two operands with a binary operator, or returns `None` if
+    /// `operator` is not one.
+    pub fn make_infix(lhs: Expression, operator: &TokenType, rhs: Expression) -> Option<Self> {
         use TokenType::*;
 
         let lhs = Box::new(lhs);
         let rhs = Box::new(rhs);
 
-        match *operator {
-            Plus | Minus | Asterisk | Slash | Percent |
-            GreaterThan | GreaterThanEquals | LessThan | LessThanEquals |
-            BitwiseAnd | BitwiseOr | BitwiseLeftShift | BitwiseRightShift | BitwiseXor |
-            And | Or => Self::Binary(lhs, BinaryOp::from(*operator), rhs),
+        Some(match *operator {
             Period => Self::Concat(lhs, rhs),
-            DoubleArrow => Self::ArrayItem { key: lhs, value: rhs },
             Equals => Self::Assign(lhs, rhs),
-            _ => unimplemented!(),
+            _ => Self::Binary(lhs, BinaryOp::from_token(*operator)?, rhs),
+        })
+    }
+}
+
+impl Expression {
+    /// The expressions nested directly inside this one. Closures and anonymous
+    /// classes start a new scope, so their bodies are not included.
+    pub fn children(&self) -> Vec<&Expression> {
+        match self {
+            Self::True | Self::False | Self::Null | Self::String(..) | Self::Integer { .. } | Self::Float { .. } => Vec::new(),
+            Self::Variable(..) | Self::TypedVariable(..) | Self::Identifier(..) | Self::MagicConst(..) => Vec::new(),
+            Self::Closure(..) | Self::AnonymousClass(..) => Vec::new(),
+            Self::Binary(lhs, _, rhs)
+            | Self::Assign(lhs, rhs)
+            | Self::AssignRef(lhs, rhs)
+            | Self::Concat(lhs, rhs)
+            | Self::PropertyAccess(lhs, rhs)
+            | Self::NullsafePropertyAccess(lhs, rhs) => vec![lhs, rhs],
+            Self::Array(items) => items.iter().flat_map(|item| item.key.iter().chain(std::iter::once(&item.value))).collect(),
+            Self::List(items) => items.iter().flatten().flat_map(|item| item.key.iter().chain(std::iter::once(&item.value))).collect(),
+            Self::ArrayAccess(target, index) => std::iter::once(&**target).chain(index.as_deref()).collect(),
+            Self::New { class: target, args } => std::iter::once(&**target).chain(args.iter().map(|arg| &arg.value)).collect(),
+            Self::Call { target, args } => std::iter::once(&**target).chain(args.arguments().iter().map(|arg| &arg.value)).collect(),
+            Self::MethodCall { target, method, args } => vec![&**target, &**method].into_iter().chain(args.arguments().iter().map(|arg| &arg.value)).collect(),
+            Self::NullsafeMethodCall { target, method, args } => vec![&**target, &**method].into_iter().chain(args.iter().map(|arg| &arg.value)).collect(),
+            Self::Unary(expression)
+            | Self::Negate(expression)
+            | Self::BitwiseNot(expression)
+            | Self::Spread(expression)
+            | Self::Reference(expression)
+            | Self::YieldFrom(expression)
+            | Self::Empty(expression)
+            | Self::Print(expression)
+            | Self::Clone(expression)
+            | Self::Include(expression)
+            | Self::IncludeOnce(expression)
+            | Self::Require(expression)
+            | Self::RequireOnce(expression)
+            | Self::Eval(expression)
+            | Self::ErrorSuppress(expression) => vec![expression],
+            Self::Isset(expressions) => expressions.iter().collect(),
+            Self::Exit(status) => status.as_deref().into_iter().collect(),
+            Self::Yield { key, value } => key.as_deref().into_iter().chain(value.as_deref()).collect(),
+        }
+    }
+
+    pub fn contains_yield(&self) -> bool {
+        matches!(self, Self::Yield { .. } | Self::YieldFrom(..)) || self.children().into_iter().any(Self::contains_yield)
+    }
+
+    pub fn as_define(&self) -> Option<Constant> {
+        let (target, args) = match self {
+            Self::Call { target, args } => (target, args),
+            _ => return None,
+        };
+
+        match (&**target, args.arguments()) {
+            (Self::Identifier(function), [name, value, ..]) if function.trim_start_matches('\\').eq_ignore_ascii_case("define") => match &name.value {
+                Self::String(name) => Some(Constant::new(name.clone(), value.value.clone())),
+                _ => None,
+            },
+            _ => None,
         }
     }
 }
 
+impl Expression {
+    /// An integer literal written in decimal, e.g. `42`.
+    pub fn integer(value: i64) -> Self {
+        Self::Integer { value, raw: value.to_string() }
+    }
+
+    /// A float literal written the way Rust formats it, e.g. `1.5`.
+    pub fn float(value: f64) -> Self {
+        Self::Float { value, raw: value.to_string() }
+    }
+}
+
 impl From<bool> for Expression {
     fn from(value: bool) -> Self {
         match value {
@@ -8,6 +8,7 @@ pub enum Flag {
     Private,
     Static,
     Abstract,
+    Readonly,
 }
 
 impl Flag {
@@ -1,6 +1,10 @@
+use crate::AttributeGroup;
 use crate::Expression;
+use crate::Nullable;
 use crate::Statement;
+use crate::Type;
 use crate::{Flag, Flaggable};
+use crate::Trivia;
 
 use serde::Serialize;
 
@@ -10,18 +14,35 @@ pub enum ClosureType {
     Short,
 }
 
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ClosureUse {
+    pub name: String,
+    pub by_ref: bool,
+}
+
+impl ClosureUse {
+    pub fn new(name: String, by_ref: bool) -> Self {
+        Self { name, by_ref }
+    }
+}
+
 #[derive(Serialize, Debug, Clone, PartialEq)]
 pub struct Function {
     pub name: Option<String>,
     pub parameters: Vec<FunctionParameter>,
-    pub body: Vec<Statement>,
-    pub return_type_hint: Option<String>,
+    pub body: Option<Vec<Statement>>,
+    pub return_type_hint: Option<Type>,
     pub flags: Vec<Flag>,
     pub closure_type: Option<ClosureType>,
+    pub uses: Vec<ClosureUse>,
+    pub by_ref: bool,
+    pub attributes: Vec<AttributeGroup>,
+    pub doc_comment: Option<String>,
+    pub trivia: Trivia,
 }
 
 impl Function {
-    pub fn new(name: Option<String>, parameters: Vec<FunctionParameter>, body: Vec<Statement>, return_type_hint: Option<String>, flags: Vec<Flag>, closure_type: Option<ClosureType>) -> Self {
+    pub fn new(name: Option<String>, parameters: Vec<FunctionParameter>, body: Option<Vec<Statement>>, return_type_hint: Option<Type>, flags: Vec<Flag>, closure_type: Option<ClosureType>) -> Self {
         Self {
             name,
             parameters,
@@ -29,8 +50,25 @@ impl Function {
             return_type_hint,
             flags,
             closure_type,
+            uses: Vec::new(),
+            by_ref: false,
+            attributes: Vec::new(),
+            doc_comment: None,
+            trivia: Trivia::default(),
         }
     }
+
+    /// Whether the function is a generator, i.e. it yields anywhere in its own
+    /// body. Yields inside nested closures belong to those closures instead.
+    pub fn is_generator(&self) -> bool {
+        fn yields(statements: &[Statement]) -> bool {
+            statements
+                .iter()
+                .any(|statement| statement.expressions().into_iter().any(Expression::contains_yield) || statement.blocks().into_iter().any(yields))
+        }
+
+        self.body.as_deref().is_some_and(yields)
+    }
 }
 
 impl Flaggable for Function {
@@ -54,12 +92,57 @@ impl Flaggable for Function {
 #[derive(Serialize, Debug, Clone, PartialEq)]
 pub struct FunctionParameter {
     pub name: String,
-    pub type_hint: Option<String>,
+    pub type_hint: Option<Type>,
     pub default: Option<Expression>,
+    pub by_ref: bool,
+    pub variadic: bool,
+    pub flags: Vec<Flag>,
+    pub attributes: Vec<AttributeGroup>,
 }
 
 impl FunctionParameter {
-    pub fn new(name: String, type_hint: Option<String>, default: Option<Expression>) -> Self {
-        Self { name, type_hint, default }
+    pub fn new(name: String, type_hint: Option<Type>, default: Option<Expression>) -> Self {
+        Self {
+            name,
+            type_hint,
+            default,
+            by_ref: false,
+            variadic: false,
+            flags: Vec::new(),
+            attributes: Vec::new(),
+        }
+    }
+
+    pub fn is_promoted(&self) -> bool {
+        self.flags.iter().any(|flag| flag.is_visibility_flag() || *flag == Flag::Readonly)
+    }
+}
+
+impl Flaggable for FunctionParameter {
+    fn add_flag(&mut self, flag: Flag) {
+        self.flags.push(flag)
+    }
+
+    fn has_flag(&self, flag: Flag) -> bool {
+        self.flags.contains(&flag)
+    }
+
+    fn has_flags(&self) -> bool {
+        !self.flags.is_empty()
+    }
+
+    fn has_visiblity_flag(&self) -> bool {
+        self.flags.iter().any(|flag| flag.is_visibility_flag())
+    }
+}
+
+impl Nullable for FunctionParameter {
+    fn is_nullable(&self) -> bool {
+        // `Foo $foo = null` is implicitly nullable
+        if self.default == Some(Expression::Null) {
+            return true;
+        }
+
+        self.type_hint.as_ref().is_some_and(Nullable::is_nullable)
     }
 }
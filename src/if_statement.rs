@@ -20,6 +20,47 @@ impl If {
             r#else,
         }
     }
+
+    pub fn condition(&self) -> &Expression {
+        &self.condition
+    }
+
+    pub fn then(&self) -> &[Statement] {
+        &self.then
+    }
+
+    pub fn else_ifs(&self) -> &[Statement] {
+        &self.else_ifs
+    }
+
+    pub fn r#else(&self) -> Option<&Statement> {
+        self.r#else.as_deref()
+    }
+
+    /// The condition along with those of every `elseif`, in order.
+    pub fn conditions(&self) -> Vec<&Expression> {
+        let mut conditions = vec![&self.condition];
+
+        for else_if in &self.else_ifs {
+            conditions.extend(else_if.expressions());
+        }
+
+        conditions
+    }
+
+    pub fn blocks(&self) -> Vec<&[Statement]> {
+        let mut blocks = vec![&self.then[..]];
+
+        for else_if in &self.else_ifs {
+            blocks.extend(else_if.blocks());
+        }
+
+        if let Some(r#else) = &self.r#else {
+            blocks.extend(r#else.blocks());
+        }
+
+        blocks
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -31,4 +72,8 @@ impl Else {
     pub fn new(then: Vec<Statement>) -> Self {
         Self { then }
     }
+
+    pub fn then(&self) -> &[Statement] {
+        &self.then
+    }
 }
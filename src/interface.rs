@@ -0,0 +1,25 @@
+use crate::Statement;
+use crate::Trivia;
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Interface {
+    name: String,
+    extends: Vec<String>,
+    body: Vec<Statement>,
+    pub doc_comment: Option<String>,
+    pub trivia: Trivia,
+}
+
+impl Interface {
+    pub fn new(name: String, extends: Vec<String>, body: Vec<Statement>) -> Self {
+        Self {
+            name,
+            extends,
+            body,
+            doc_comment: None,
+            trivia: Trivia::default(),
+        }
+    }
+}
@@ -1,25 +1,47 @@
 #![feature(bindings_after_at)]
 #![feature(label_break_value)]
 
-use tusk_lexer::Lexer;
 use wasm_bindgen::prelude::*;
 
+mod analysis;
+mod argument;
+mod array_item;
+mod attribute;
 mod binary_op;
 mod binding_power;
 mod class;
+mod comment;
+mod constant;
 mod error;
 mod expression;
 mod flag;
 mod function;
 mod if_statement;
+mod interface;
+mod magic_const;
+mod list_item;
 mod parser;
+mod phpdoc;
 mod property;
 mod statement;
+mod static_var;
+mod token_stream;
+mod type_hint;
 mod nullable;
 
+pub use analysis::find_constants;
+pub use argument::Argument;
+pub use argument::ArgumentList;
+pub use array_item::ArrayItem;
+pub use attribute::Attribute;
+pub use attribute::AttributeGroup;
 pub use binary_op::BinaryOp;
 pub use binding_power::BindingPower;
 pub use class::Class;
+pub use comment::Comment;
+pub use comment::Trivia;
+pub use constant::ClassConst;
+pub use constant::Constant;
 pub use error::ParserError;
 pub use expression::Expression;
 pub use flag::Flag;
@@ -27,20 +49,29 @@ pub use flag::Flaggable;
 pub use function::Function;
 pub use function::FunctionParameter;
 pub use function::ClosureType;
+pub use function::ClosureUse;
 pub use if_statement::Else;
 pub use if_statement::If;
+pub use interface::Interface;
+pub use list_item::ListItem;
+pub use magic_const::MagicConst;
 pub use nullable::Nullable;
 pub use parser::Parser;
+pub use phpdoc::DocBlock;
+pub use phpdoc::DocType;
+pub use phpdoc::ShapeField;
+pub use phpdoc::Tag;
 pub use property::Property;
 pub use statement::Statement;
+pub use static_var::StaticVar;
+pub use type_hint::Type;
 
 #[wasm_bindgen]
 #[no_mangle]
-pub fn parse(source: &str) -> JsValue {
-    let lexer = Lexer::new(source);
-    let mut parser = Parser::new(lexer);
+pub fn parse(source: &str) -> Result<JsValue, JsValue> {
+    let mut parser = Parser::from_source(source);
 
-    let program = parser.all();
+    let program = parser.all().map_err(|error| JsValue::from_str(&error.to_string()))?;
 
-    JsValue::from_serde(&program.unwrap()).unwrap()
+    JsValue::from_serde(&program).map_err(|error| JsValue::from_str(&error.to_string()))
 }
@@ -0,0 +1,18 @@
+use crate::Expression;
+
+use serde::Serialize;
+
+/// A slot in a `list()` or `[...]` destructuring pattern. Nested patterns are
+/// stored as `Expression::List` values.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ListItem {
+    pub key: Option<Expression>,
+    pub value: Expression,
+    pub by_ref: bool,
+}
+
+impl ListItem {
+    pub fn new(key: Option<Expression>, value: Expression, by_ref: bool) -> Self {
+        Self { key, value, by_ref }
+    }
+}
@@ -0,0 +1,29 @@
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum MagicConst {
+    Line,
+    File,
+    Dir,
+    Class,
+    Function,
+    Method,
+    Namespace,
+    Trait,
+}
+
+impl MagicConst {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_uppercase().as_str() {
+            "__LINE__" => Self::Line,
+            "__FILE__" => Self::File,
+            "__DIR__" => Self::Dir,
+            "__CLASS__" => Self::Class,
+            "__FUNCTION__" => Self::Function,
+            "__METHOD__" => Self::Method,
+            "__NAMESPACE__" => Self::Namespace,
+            "__TRAIT__" => Self::Trait,
+            _ => return None,
+        })
+    }
+}
@@ -1,1243 +1,2156 @@
+use crate::{Argument, ArgumentList};
+use crate::ArrayItem;
+use crate::{Attribute, AttributeGroup};
 use crate::BindingPower;
 use crate::Class;
+use crate::Comment;
+use crate::{ClassConst, Constant};
 use crate::Expression;
 use crate::ParserError;
 use crate::Property;
 use crate::Statement;
+use crate::StaticVar;
+use crate::Type;
 use crate::{Else, If};
+use crate::Interface;
+use crate::ListItem;
+use crate::MagicConst;
 use crate::{Flag, Flaggable};
 use crate::Nullable;
-use crate::{Function, FunctionParameter, ClosureType};
+use crate::{Function, FunctionParameter, ClosureType, ClosureUse};
 
 use std::borrow::BorrowMut;
 use std::iter::Iterator;
+use crate::token_stream::TokenStream;
+
 use tusk_lexer::{Lexer, Token, TokenType};
 
 type Program = Vec<Statement>;
 
+/// How deeply statements and expressions may nest before parsing gives up,
+/// so that hostile input cannot overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+/// Everything in a function declaration before its body.
+struct Signature {
+    parameters: Vec<FunctionParameter>,
+    uses: Vec<ClosureUse>,
+    return_type_hint: Option<Type>,
+}
+
 pub struct Parser<'p> {
-    lexer: Lexer<'p>,
+    lexer: TokenStream<'p>,
+    source: Option<&'p str>,
+    depth: usize,
 }
 
 impl<'p> Parser<'p> {
     pub fn new(lexer: Lexer<'p>) -> Self {
-        Self { lexer }
+        Self { lexer: TokenStream::new(lexer, None), source: None, depth: 0 }
+    }
+
+    /// Creates a parser for `source`. Unlike `Parser::new`, this keeps hold of
+    /// the source so that the raw data after `__halt_compiler();` is available,
+    /// and so that a comment on the same line as the end of a declaration trails
+    /// it rather than leading the next one.
+    pub fn from_source(source: &'p str) -> Self {
+        Self { lexer: TokenStream::new(Lexer::new(source), Some(source)), source: Some(source), depth: 0 }
+    }
+
+    /// Runs `parse` one level deeper, failing once `MAX_DEPTH` is reached.
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T, ParserError<'p>>) -> Result<T, ParserError<'p>> {
+        if self.depth >= MAX_DEPTH {
+            return Err(ParserError::MaximumDepthExceeded(MAX_DEPTH));
+        }
+
+        self.depth += 1;
+
+        let result = parse(self);
+
+        self.depth -= 1;
+
+        result
     }
 
-    #[allow(clippy::needless_collect)]
     fn match_token(&mut self, token: Token<'p>) -> Result<Statement, ParserError<'p>> {
-        let kind = token.kind;
+        self.with_comments(|parser| parser.nested(|parser| parser.parse_statement(token)))
+    }
 
-        Ok(match kind {
-            TokenType::OpenTag => Statement::OpenTag,
-            TokenType::DocBlockComment => Statement::DocBlock(token.slice.to_owned()),
-            TokenType::Break => {
-                self.expect_token(TokenType::SemiColon, ";")?;
+    /// Parses a member of a class or interface body, where `const` declares class
+    /// constants and `static` declares static properties.
+    fn match_member(&mut self, token: Token<'p>) -> Result<Statement, ParserError<'p>> {
+        self.with_comments(|parser| {
+            Ok(match parser.nested(|parser| parser.parse_statement(token))? {
+                Statement::Const(constants) => Statement::ClassConst(ClassConst::new(constants, None, Vec::new())),
+                Statement::StaticVar(vars) => Self::static_property(vars)?,
+                statement => statement,
+            })
+        })
+    }
 
-                Statement::Break
-            },
-            TokenType::Continue => {
-                let next = self.lexer.peek();
+    /// Runs `parse`, which has just consumed the first token of a statement, and
+    /// gives the statement the comments around it. Those that it cannot hold are
+    /// set aside as unclaimed.
+    fn with_comments(&mut self, parse: impl FnOnce(&mut Self) -> Result<Statement, ParserError<'p>>) -> Result<Statement, ParserError<'p>> {
+        let start = self.lexer.position() - 1;
+        let leading = self.lexer.take_comments(start);
 
-                match next {
-                    Some(Token { kind: TokenType::SemiColon, .. }) => {
-                        self.lexer.next();
+        let mut statement = parse(self)?;
 
-                        Statement::Continue(None)
-                    },
-                    None => return Err(ParserError::UnexpectedEndOfFile),
-                    _ => {
-                        let expression = self.parse_expression(0, None)?;  
-                        
-                        self.expect_token(TokenType::SemiColon, ";")?;
+        let end = self.lexer.position();
+        let trailing = match self.lexer.peek() {
+            None | Some(Token { kind: TokenType::RightBrace, .. }) => self.lexer.take_comments(end),
+            _ => self.lexer.take_trailing_comments(end),
+        };
 
-                        Statement::Continue(Some(expression))
-                    }
+        // Comments inside the statement that no nested declaration claimed.
+        self.lexer.release_comments(start + 1..end);
+
+        match statement.comments_mut() {
+            Some((doc_comment, trivia)) => {
+                if doc_comment.is_none() {
+                    *doc_comment = leading.iter().rev().find(|comment| comment.is_doc_block()).map(|comment| comment.text().to_owned());
                 }
+
+                trivia.leading.splice(0..0, leading);
+                trivia.trailing.extend(trailing);
             },
-            TokenType::Use => {
-                let expression = self.parse_expression(0, None)?;
+            None => {
+                self.lexer.release(start, leading);
+                self.lexer.release(end, trailing);
+            },
+        }
 
-                match expression {
-                    Expression::Identifier(..) => {
-                        self.expect_token(TokenType::SemiColon, ";")?;
-                        
-                        Statement::Use(expression)
-                    },
-                    _ => return Err(ParserError::UnexpectedExpression(expression))
+        Ok(statement)
+    }
+
+    /// Parses a statement outside of a class body, where class-only declarations
+    /// such as `public const X = 1;` and functions without a body are not allowed,
+    /// and validates any class it declares once its modifiers are known.
+    fn match_statement(&mut self, token: Token<'p>) -> Result<Statement, ParserError<'p>> {
+        let statement = self.match_token(token)?;
+
+        match &statement {
+            Statement::Class(class) => Self::validate_class(class)?,
+            Statement::ClassConst(..) => return Err(ParserError::UnexpectedStatement(Box::new(statement))),
+            // only allowed at the top level, not in function bodies or other blocks
+            Statement::Const(..) | Statement::Namespace(..) if self.depth > 0 => return Err(ParserError::UnexpectedStatement(Box::new(statement))),
+            Statement::Function(function @ Function { body: None, .. }) => {
+                return Err(ParserError::MethodWithoutBody(function.name.clone().unwrap_or_default()));
+            },
+            // class bodies don't come through here, so this is never a method
+            Statement::Function(function) => {
+                if let Some(parameter) = function.parameters.iter().find(|parameter| parameter.is_promoted()) {
+                    return Err(ParserError::PromotedPropertyOutsideConstructor(parameter.name.clone()));
                 }
             },
-            TokenType::Echo => {
-                let expression = self.parse_expression(0, None)?;
+            _ => (),
+        }
 
-                self.expect_token(TokenType::SemiColon, ";")?;
+        Ok(statement)
+    }
+
+    #[allow(clippy::needless_collect)]
+    fn parse_statement(&mut self, token: Token<'p>) -> Result<Statement, ParserError<'p>> {
+        // Every arm is its own function, which keeps this frame small however
+        // deeply statements nest.
+        match token.kind {
+            TokenType::OpenTag => Ok(Statement::OpenTag),
+            TokenType::Break => self.parse_optional_expression().map(Statement::Break),
+            TokenType::Continue => self.parse_optional_expression().map(Statement::Continue),
+            TokenType::Return => self.parse_optional_expression().map(Statement::Return),
+            TokenType::Goto => self.parse_goto(),
+            TokenType::Identifier if token.slice.eq_ignore_ascii_case("__halt_compiler") => self.parse_halt_compiler(),
+            TokenType::Identifier if matches!(self.lexer.peek(), Some(Token { kind: TokenType::Colon, .. })) => {
+                self.lexer.next();
 
-                Statement::Echo(expression)
+                Ok(Statement::Label(token.slice.to_owned()))
             },
-            TokenType::While => {
-                self.expect_left_paren()?;
+            TokenType::Declare => self.parse_declare(),
+            TokenType::Namespace => self.parse_namespace(),
+            TokenType::Use => self.parse_use(),
+            TokenType::Global => self.parse_global(),
+            TokenType::Unset => self.parse_unset(),
+            TokenType::Echo => self.parse_echo(),
+            TokenType::While => self.parse_while(),
+            TokenType::Do => self.parse_do_while(),
+            TokenType::Foreach => self.parse_foreach(),
+            TokenType::If => self.parse_if(),
+            TokenType::Public | TokenType::Protected | TokenType::Private | TokenType::Final | TokenType::Abstract | TokenType::Static | TokenType::Readonly => self.parse_modified_statement(token),
+            TokenType::Attribute => self.parse_attributed_statement(),
+            TokenType::Interface => self.parse_interface(),
+            TokenType::Const => self.parse_const(),
+            TokenType::Class => {
+                let name = self.expect_token(TokenType::Identifier, "")?;
+
+                self.parse_class(Some(name.slice.to_owned())).map(Statement::Class)
+            },
+            TokenType::Function => self.parse_function(),
+            TokenType::String | TokenType::Integer | TokenType::Float => self.parse_literal_statement(token),
+            _ => self.parse_expression_statement(token),
+        }
+    }
 
-                let condition = self.parse_expression(0, None)?;
+    fn parse_goto(&mut self) -> Result<Statement, ParserError<'p>> {
+        let label = self.expect_token(TokenType::Identifier, "")?;
 
-                self.expect_right_paren()?;
-                self.expect_left_brace()?;
+        self.expect_token(TokenType::SemiColon, ";")?;
 
-                let mut body = Vec::new();
-                let mut did_find_right_brace = false;
+        Ok(Statement::Goto(label.slice.to_owned()))
+    }
 
-                loop {
-                    let next = self.lexer.next();
+    fn parse_halt_compiler(&mut self) -> Result<Statement, ParserError<'p>> {
+        self.expect_left_paren()?;
+        self.expect_right_paren()?;
 
-                    match next {
-                        Some(Token {
-                            kind: TokenType::RightBrace, ..
-                        }) => {
-                            did_find_right_brace = true;
+        let end = self.expect_token(TokenType::SemiColon, ";")?;
 
-                            break;
-                        }
-                        None => return Err(ParserError::UnexpectedEndOfFile),
-                        _ => {
-                            let statement = self.match_token(next.unwrap())?;
+        let data = self.source.map(|source| {
+            let offset = end.slice.as_ptr() as usize + end.slice.len() - source.as_ptr() as usize;
 
-                            body.push(statement);
-                        }
-                    }
-                }
+            source[offset..].to_owned()
+        });
 
-                if !did_find_right_brace {
-                    self.expect_right_brace()?;
-                }
+        // everything after the halt is data, not code
+        while self.lexer.next().is_some() {}
+
+        Ok(Statement::HaltCompiler(data))
+    }
+
+    fn parse_namespace(&mut self) -> Result<Statement, ParserError<'p>> {
+        let expression = self.parse_expression(0, None)?;
+
+        match expression {
+            Expression::Identifier(..) => {
+                self.expect_token(TokenType::SemiColon, ";")?;
+
+                Ok(Statement::Namespace(expression))
+            },
+            _ => Err(ParserError::UnexpectedExpression(Box::new(expression)))
+        }
+    }
+
+    fn parse_use(&mut self) -> Result<Statement, ParserError<'p>> {
+        let expression = self.parse_expression(0, None)?;
 
-                Statement::While { condition, body }
+        match expression {
+            Expression::Identifier(..) => {
+                self.expect_token(TokenType::SemiColon, ";")?;
+
+                Ok(Statement::Use(expression))
             },
-            TokenType::Do => {
-                self.expect_left_brace()?;
+            _ => Err(ParserError::UnexpectedExpression(Box::new(expression)))
+        }
+    }
 
-                let mut body = Vec::new();
-                let mut did_find_right_brace = false;
+    fn parse_global(&mut self) -> Result<Statement, ParserError<'p>> {
+        let mut names = Vec::new();
 
-                loop {
-                    let next = self.lexer.next();
+        loop {
+            let variable = self.expect_token(TokenType::Variable, "")?;
 
-                    match next {
-                        Some(Token {
-                            kind: TokenType::RightBrace, ..
-                        }) => {
-                            did_find_right_brace = true;
+            names.push(variable.slice[1..].to_owned());
 
-                            break;
-                        }
-                        None => return Err(ParserError::UnexpectedEndOfFile),
-                        _ => {
-                            let statement = self.match_token(next.unwrap())?;
+            match self.lexer.next() {
+                Some(Token { kind: TokenType::Comma, .. }) => (),
+                Some(Token { kind: TokenType::SemiColon, .. }) => break,
+                Some(token) => return Err(ParserError::UnexpectedToken(token.kind, token.slice)),
+                None => return Err(ParserError::UnexpectedEndOfFile),
+            }
+        }
 
-                            body.push(statement);
-                        }
-                    }
-                }
+        Ok(Statement::Global(names))
+    }
 
-                if !did_find_right_brace {
-                    self.expect_right_brace()?;
-                }
+    fn parse_unset(&mut self) -> Result<Statement, ParserError<'p>> {
+        self.expect_left_paren()?;
 
-                self.expect_token(TokenType::While, "while")?;
-                self.expect_left_paren()?;
+        let expressions = self.parse_construct_arguments()?;
 
-                let condition = self.parse_expression(0, None)?;
+        self.expect_token(TokenType::SemiColon, ";")?;
 
-                self.expect_right_paren()?;
-                self.expect_token(TokenType::SemiColon, ";")?;
+        Ok(Statement::Unset(expressions))
+    }
+
+    fn parse_echo(&mut self) -> Result<Statement, ParserError<'p>> {
+        let expression = self.parse_expression(0, None)?;
+
+        self.expect_token(TokenType::SemiColon, ";")?;
+
+        Ok(Statement::Echo(expression))
+    }
+
+    fn parse_const(&mut self) -> Result<Statement, ParserError<'p>> {
+        let mut type_hint = None;
+        // typed constants, e.g. `const int LIMIT = 10;`
+        if !matches!(self.lexer.peek_nth(1), Some(Token { kind: TokenType::Equals, .. })) {
+            let next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
+
+            type_hint = Some(self.parse_type(next)?);
+        }
+
+        let constants = self.parse_constants()?;
+
+        Ok(match type_hint {
+            Some(..) => Statement::ClassConst(ClassConst::new(constants, type_hint, Vec::new())),
+            None => Statement::Const(constants),
+        })
+    }
+
+    fn parse_literal_statement(&mut self, token: Token<'p>) -> Result<Statement, ParserError<'p>> {
+        let expression = match token.kind {
+            TokenType::String => {
+                let mut buffer: String = token.slice.to_string();
+
+                buffer.remove(0);
+                buffer.pop();
 
-                Statement::DoWhile { condition, body }
+                Expression::String(buffer)
             },
-            TokenType::Foreach => {
-                self.expect_left_paren()?;
+            TokenType::Integer => Self::parse_integer(token.slice)?,
+            _ => Self::parse_float(token.slice)?,
+        };
 
-                let left_hand = self.parse_expression(0, None)?;
+        // a bare literal may leave out its semicolon, e.g. `12345 'Hello'`
+        if matches!(self.lexer.peek(), Some(Token { kind: TokenType::SemiColon, .. })) {
+            self.lexer.next();
+        }
 
-                self.expect_token(TokenType::As, "as")?;
+        Ok(Statement::Expression(expression))
+    }
 
-                let right_hand = self.parse_expression(0, None)?;
-                let mut key_var = None;
-                let mut value_var: Expression;
+    fn parse_expression_statement(&mut self, token: Token<'p>) -> Result<Statement, ParserError<'p>> {
+        let expression = self.parse_expression(0, Some(token))?;
 
-                match right_hand {
-                    Expression::Variable(..) => {
-                        value_var = right_hand
-                    },
-                    Expression::ArrayItem { key, value } => {
-                        key_var = Some(*key);
-                        value_var = *value
-                    },
-                    _ => {
-                        return Err(ParserError::UnexpectedExpression(right_hand))
-                    }
-                };
+        self.expect_token(TokenType::SemiColon, ";")?;
 
-                self.expect_right_paren()?;
-                self.expect_left_brace()?;
+        Ok(Statement::Expression(expression))
+    }
 
-                let mut body = Vec::new();
-                let mut did_find_right_brace = false;
+    fn parse_declare(&mut self) -> Result<Statement, ParserError<'p>> {
+        self.expect_left_paren()?;
 
-                loop {
-                    let next = self.lexer.next();
+        let mut directives = Vec::new();
 
-                    match next {
-                        Some(Token {
-                            kind: TokenType::RightBrace, ..
-                        }) => {
-                            did_find_right_brace = true;
+        loop {
+            let name = self.expect_token(TokenType::Identifier, "")?;
 
-                            break;
-                        }
-                        None => return Err(ParserError::UnexpectedEndOfFile),
-                        _ => {
-                            let statement = self.match_token(next.unwrap())?;
+            self.expect_token(TokenType::Equals, "=")?;
 
-                            body.push(statement);
-                        }
-                    }
-                }
+            directives.push(Constant::new(name.slice.to_owned(), self.parse_expression(0, None)?));
 
-                if !did_find_right_brace {
-                    self.expect_right_brace()?;
-                }
+            match self.lexer.next() {
+                Some(Token { kind: TokenType::Comma, .. }) => (),
+                Some(Token { kind: TokenType::RightParen, .. }) => break,
+                Some(token) => return Err(ParserError::UnexpectedToken(token.kind, token.slice)),
+                None => return Err(ParserError::UnexpectedEndOfFile),
+            }
+        }
 
-                Statement::Foreach {
-                    expression: left_hand,
-                    key_var: key_var,
-                    value_var: value_var,
-                    body: body,
-                }
+        let body = match self.lexer.next() {
+            Some(Token { kind: TokenType::SemiColon, .. }) => Vec::new(),
+            Some(Token { kind: TokenType::LeftBrace, .. }) => self.parse_block(TokenType::RightBrace)?,
+            Some(Token { kind: TokenType::Colon, .. }) => {
+                let body = self.parse_block(TokenType::EndDeclare)?;
+
+                self.expect_token(TokenType::SemiColon, ";")?;
+
+                body
             },
-            TokenType::If => {
-                self.expect_left_paren()?;
+            Some(token) => vec![self.match_statement(token)?],
+            None => return Err(ParserError::UnexpectedEndOfFile),
+        };
 
-                let condition = self.parse_expression(0, None)?;
+        Ok(Statement::Declare { directives, body })
+    }
 
-                self.expect_right_paren()?;
-                self.expect_left_brace()?;
+    fn parse_while(&mut self) -> Result<Statement, ParserError<'p>> {
+        self.expect_left_paren()?;
 
-                let mut body = Vec::new();
-                let mut did_find_right_brace = false;
+        let condition = self.parse_expression(0, None)?;
 
-                loop {
-                    let next = self.lexer.next();
+        self.expect_right_paren()?;
+        self.expect_left_brace()?;
 
-                    match next {
-                        Some(Token {
-                            kind: TokenType::RightBrace, ..
-                        }) => {
-                            did_find_right_brace = true;
+        let body = self.parse_block(TokenType::RightBrace)?;
 
-                            break;
-                        }
-                        None => return Err(ParserError::UnexpectedEndOfFile),
-                        _ => {
-                            let statement = self.match_token(next.unwrap())?;
+        Ok(Statement::While { condition, body })
+    }
 
-                            body.push(statement);
-                        }
-                    }
-                }
+    fn parse_do_while(&mut self) -> Result<Statement, ParserError<'p>> {
+        self.expect_left_brace()?;
 
-                if !did_find_right_brace {
-                    self.expect_right_brace()?;
-                }
+        let body = self.parse_block(TokenType::RightBrace)?;
 
-                let mut else_ifs = Vec::new();
-                let mut r#else = None;
+        self.expect_token(TokenType::While, "while")?;
+        self.expect_left_paren()?;
 
-                loop {
-                    let next = self.lexer.peek();
+        let condition = self.parse_expression(0, None)?;
 
-                    let next = match next {
-                        Some(Token { kind: TokenType::ElseIf | TokenType::Else, .. }) => {
-                            let next = self.lexer.next();
+        self.expect_right_paren()?;
+        self.expect_token(TokenType::SemiColon, ";")?;
 
-                            next.unwrap()
-                        },
-                        _ => break,
-                    };
+        Ok(Statement::DoWhile { condition, body })
+    }
 
-                    match next.kind {
-                        TokenType::ElseIf => {
-                            self.expect_left_paren()?;
+    fn parse_foreach(&mut self) -> Result<Statement, ParserError<'p>> {
+        self.expect_left_paren()?;
 
-                            let condition = self.parse_expression(0, None)?;
+        let left_hand = self.parse_expression(0, None)?;
 
-                            self.expect_right_paren()?;
-                            self.expect_left_brace()?;
+        self.expect_token(TokenType::As, "as")?;
 
-                            let mut body = Vec::new();
-                            let mut did_find_right_brace = false;
+        let mut key_var = None;
+        let mut by_ref = self.consume_by_ref();
+        let mut value_var = self.parse_list_target()?;
 
-                            loop {
-                                let next = self.lexer.next();
+        if !by_ref && matches!(self.lexer.peek(), Some(Token { kind: TokenType::DoubleArrow, .. })) {
+            self.lexer.next();
 
-                                match next {
-                                    Some(Token {
-                                        kind: TokenType::RightBrace, ..
-                                    }) => {
-                                        did_find_right_brace = true;
+            key_var = Some(value_var);
+            by_ref = self.consume_by_ref();
+            value_var = self.parse_list_target()?;
+        }
 
-                                        break;
-                                    }
-                                    None => return Err(ParserError::UnexpectedEndOfFile),
-                                    _ => {
-                                        let statement = self.match_token(next.unwrap())?;
+        if !matches!(value_var, Expression::Variable(..) | Expression::List(..)) {
+            return Err(ParserError::UnexpectedExpression(Box::new(value_var)));
+        }
 
-                                        body.push(statement);
-                                    }
-                                }
-                            }
+        // `&[$a]` isn't valid, only `[&$a]`
+        if by_ref && matches!(value_var, Expression::List(..)) {
+            return Err(ParserError::ListByReference);
+        }
 
-                            if !did_find_right_brace {
-                                self.expect_right_brace()?;
-                            }
+        self.expect_right_paren()?;
+        self.expect_left_brace()?;
 
-                            else_ifs.push(Statement::ElseIf(If::new(condition, body, Vec::new(), None)))
-                        },
-                        TokenType::Else => {
-                            let mut condition = None;
-                            let mut else_if = false;
-
-                            match self.lexer.peek() {
-                                Some(Token { kind: TokenType::If, .. }) => {
-                                    self.lexer.next();
-                                    else_if = true;
-                                    self.expect_left_paren()?;
-                                    condition = Some(self.parse_expression(0, None)?);
-                                    self.expect_right_paren()?;
-                                },
-                                _ => (),
-                                None => return Err(ParserError::UnexpectedEndOfFile),
-                            };
-
-                            self.expect_left_brace()?;
-
-                            let mut body = Vec::new();
-                            let mut did_find_right_brace = false;
-
-                            loop {
-                                let next = self.lexer.next();
-
-                                match next {
-                                    Some(Token {
-                                        kind: TokenType::RightBrace, ..
-                                    }) => {
-                                        did_find_right_brace = true;
-
-                                        break;
-                                    }
-                                    None => return Err(ParserError::UnexpectedEndOfFile),
-                                    _ => {
-                                        let statement = self.match_token(next.unwrap())?;
-
-                                        body.push(statement);
-                                    }
-                                }
-                            }
+        let body = self.parse_block(TokenType::RightBrace)?;
 
-                            if !did_find_right_brace {
-                                self.expect_right_brace()?;
-                            }
+        Ok(Statement::Foreach {
+            expression: left_hand,
+            key_var,
+            value_var,
+            by_ref,
+            body,
+        })
+    }
 
-                            if else_if {
-                                else_ifs.push(Statement::ElseIf(If::new(condition.unwrap(), body, Vec::new(), None)));
-                            } else {
-                                r#else = Some(Box::new(Statement::Else(Else::new(body))))
-                            }
-                        }
-                        _ => return Err(ParserError::UnexpectedToken(next.kind, next.slice)),
-                    }
-                }
+    fn parse_if(&mut self) -> Result<Statement, ParserError<'p>> {
+        self.expect_left_paren()?;
 
-                Statement::If(If::new(condition, body, else_ifs, r#else))
-            }
-            TokenType::Return => {
-                let expression = self.parse_expression(0, None)?;
+        let condition = self.parse_expression(0, None)?;
 
-                self.expect_token(TokenType::SemiColon, ";")?;
+        self.expect_right_paren()?;
+        self.expect_left_brace()?;
 
-                Statement::Return(expression)
-            }
-            flag @ (TokenType::Public | TokenType::Protected | TokenType::Private | TokenType::Final | TokenType::Abstract | TokenType::Static) => {
-                let next = self.lexer.next();
+        let body = self.parse_block(TokenType::RightBrace)?;
 
-                if next.is_none() {
-                    return Err(ParserError::UnexpectedEndOfFile);
-                }
+        let mut else_ifs = Vec::new();
+        let mut r#else = None;
 
-                let mut statement = self.match_token(next.unwrap())?;
+        loop {
+            let next = self.lexer.peek();
 
-                let flag_type = match flag {
-                    TokenType::Public => Flag::Public,
-                    TokenType::Protected => Flag::Protected,
-                    TokenType::Private => Flag::Private,
-                    TokenType::Final => Flag::Final,
-                    TokenType::Abstract => Flag::Abstract,
-                    TokenType::Static => Flag::Static,
-                    _ => unreachable!(),
-                };
+            let next = match next {
+                Some(Token { kind: TokenType::ElseIf | TokenType::Else, .. }) => {
+                    let next = self.lexer.next();
 
-                match statement {
-                    Statement::Function(ref mut function) => {
-                        if flag_type == Flag::Final && function.has_flag(Flag::Abstract) {
-                            return Err(ParserError::FlagNotAllowed(flag_type, "abstract methods.".to_owned()));
-                        }
+                    next.unwrap()
+                },
+                _ => break,
+            };
 
-                        if flag_type == Flag::Abstract && function.has_flag(Flag::Final) {
-                            return Err(ParserError::FlagNotAllowed(flag_type, "final methods".to_owned()));
-                        }
+            match next.kind {
+                TokenType::ElseIf => {
+                    self.expect_left_paren()?;
 
-                        function.add_flag(flag_type)
-                    }
-                    Statement::Class(ref mut class) => {
-                        if matches!(flag_type, Flag::Final) && class.has_flag(Flag::Abstract) {
-                            return Err(ParserError::FlagNotAllowed(flag_type, "abstract classes.".to_owned()));
-                        }
+                    let condition = self.parse_expression(0, None)?;
 
-                        if matches!(flag_type, Flag::Abstract) && class.has_flag(Flag::Final) {
-                            return Err(ParserError::FlagNotAllowed(flag_type, "final classes.".to_owned()));
-                        }
+                    self.expect_right_paren()?;
+                    self.expect_left_brace()?;
 
-                        class.add_flag(flag_type)
-                    }
-                    Statement::Expression(Expression::TypedVariable(ref type_hint, ref variable)) => {
-                        let mut property = Property::new(variable.clone(), Vec::new(), Some(type_hint.clone()), None);
+                    let body = self.parse_block(TokenType::RightBrace)?;
 
-                        property.add_flag(flag_type);
+                    else_ifs.push(Statement::ElseIf(If::new(condition, body, Vec::new(), None)))
+                },
+                TokenType::Else => {
+                    let mut condition = None;
+                    let mut else_if = false;
 
-                        statement = Statement::Property(property)
-                    }
-                    Statement::Property(ref mut property) => {
-                        if flag_type == Flag::Final || flag_type == Flag::Abstract {
-                            return Err(ParserError::FlagNotAllowed(flag_type, "properties".to_owned()));
-                        }
+                    match self.lexer.peek() {
+                        Some(Token { kind: TokenType::If, .. }) => {
+                            self.lexer.next();
+                            else_if = true;
+                            self.expect_left_paren()?;
+                            condition = Some(self.parse_expression(0, None)?);
+                            self.expect_right_paren()?;
+                        },
+                        _ => (),
+                        None => return Err(ParserError::UnexpectedEndOfFile),
+                    };
 
-                        if property.has_flag(flag_type) {
-                            return Err(ParserError::DuplicateFlag(flag_type));
-                        }
+                    self.expect_left_brace()?;
 
-                        if flag_type.is_visibility_flag() && property.has_visiblity_flag() {
-                            return Err(ParserError::FlagNotAllowed(flag_type, "properties with existing visiblity flags".to_owned()));
-                        }
+                    let body = self.parse_block(TokenType::RightBrace)?;
 
-                        property.add_flag(flag_type)
+                    if else_if {
+                        else_ifs.push(Statement::ElseIf(If::new(condition.unwrap(), body, Vec::new(), None)));
+                    } else {
+                        r#else = Some(Box::new(Statement::Else(Else::new(body))))
                     }
-                    Statement::Expression(Expression::Assign(ref variable, ref default)) => {}
-                    _ => return Err(ParserError::Unknown),
                 }
-
-                statement
+                _ => return Err(ParserError::UnexpectedToken(next.kind, next.slice)),
             }
-            TokenType::Class => {
-                let name = self.expect_token(TokenType::Identifier, "")?;
-                let mut implements = Vec::new();
-                let mut extends = String::new();
+        }
 
-                'outer: loop {
-                    let next = self.lexer.next();
+        Ok(Statement::If(If::new(condition, body, else_ifs, r#else)))
+    }
 
-                    match next {
-                        Some(Token { kind: TokenType::Extends, .. }) => {
-                            if !implements.is_empty() {
-                                let t = next.unwrap();
+    fn parse_modified_statement(&mut self, token: Token<'p>) -> Result<Statement, ParserError<'p>> {
+        let flag = token.kind;
+        let next = self.lexer.next();
 
-                                return Err(ParserError::UnexpectedToken(t.kind, t.slice));
-                            }
+        let mut statement = match next {
+            // function-local static variables, e.g. `static $count = 0;`
+            Some(t) if flag == TokenType::Static && t.kind == TokenType::Variable => return Ok(Statement::StaticVar(self.parse_static_vars(t)?)),
+            Some(t) if t.kind == TokenType::Variable || Self::is_type_start(t.kind) => Statement::Property(self.parse_property(t)?),
+            Some(t) => self.match_token(t)?,
+            None => return Err(ParserError::UnexpectedEndOfFile),
+        };
 
-                            let identifier = self.expect_token(TokenType::Identifier, "")?;
+        let flag_type = match flag {
+            TokenType::Public => Flag::Public,
+            TokenType::Protected => Flag::Protected,
+            TokenType::Private => Flag::Private,
+            TokenType::Final => Flag::Final,
+            TokenType::Abstract => Flag::Abstract,
+            TokenType::Static => Flag::Static,
+            TokenType::Readonly => Flag::Readonly,
+            _ => return Err(ParserError::UnexpectedToken(flag, token.slice)),
+        };
 
-                            extends = identifier.slice.to_string();
-                        }
-                        Some(Token {
-                            kind: TokenType::Implements, ..
-                        }) => {
-                            let identifier = self.expect_token(TokenType::Identifier, "")?;
-
-                            implements.push(identifier.slice.to_string());
-
-                            loop {
-                                let next = self.lexer.next();
-
-                                match next {
-                                    Some(Token {
-                                        kind: TokenType::Identifier, ..
-                                    }) => {
-                                        implements.push(next.unwrap().slice.to_string());
-                                    }
-                                    Some(Token { kind: TokenType::Comma, .. }) => {
-                                        let identifier = self.expect_token(TokenType::Identifier, "")?;
-
-                                        implements.push(identifier.slice.to_string());
-                                    }
-                                    Some(Token {
-                                        kind: TokenType::LeftBrace, ..
-                                    }) => {
-                                        if !implements.is_empty() {
-                                            break 'outer;
-                                        }
-
-                                        continue;
-                                    }
-                                    None => return Err(ParserError::UnexpectedEndOfFile),
-                                    _ => {
-                                        let t = next.unwrap();
-
-                                        return Err(ParserError::UnexpectedToken(t.kind, t.slice));
-                                    }
-                                }
-                            }
-                        }
-                        Some(Token {
-                            kind: TokenType::LeftBrace, ..
-                        }) => break,
-                        _ => return Err(ParserError::Unknown),
-                    }
+        statement = match statement {
+            // modifiers are only allowed on class constants, e.g. `private const LIMIT = 10;`
+            Statement::Const(constants) => Statement::ClassConst(ClassConst::new(constants, None, Vec::new())),
+            // and on static properties, e.g. `public static $instance;`
+            Statement::StaticVar(vars) => Self::static_property(vars)?,
+            statement => statement,
+        };
+
+        match statement {
+            Statement::Function(ref mut function) => {
+                if flag_type == Flag::Final && function.has_flag(Flag::Abstract) {
+                    return Err(ParserError::FlagNotAllowed(flag_type, "abstract methods.".to_owned()));
                 }
 
-                let mut body: Vec<Statement> = Vec::new();
+                if flag_type == Flag::Abstract && function.has_flag(Flag::Final) {
+                    return Err(ParserError::FlagNotAllowed(flag_type, "final methods".to_owned()));
+                }
 
-                loop {
-                    let next = self.lexer.next();
+                if flag_type == Flag::Readonly {
+                    return Err(ParserError::FlagNotAllowed(flag_type, "methods".to_owned()));
+                }
 
-                    match next {
-                        Some(Token {
-                            kind: TokenType::RightBrace, ..
-                        }) => break,
-                        None => return Err(ParserError::UnexpectedEndOfFile),
-                        _ => {
-                            let mut statement = self.match_token(next.unwrap())?;
-
-                            match &statement {
-                                Statement::Function(Function { name: function_name, .. }) => {
-                                    let matches: Vec<Statement> = body
-                                        .clone()
-                                        .into_iter()
-                                        .filter(|statement| match statement {
-                                            Statement::Function(function) => function.name == *function_name,
-                                            _ => false,
-                                        })
-                                        .collect();
-
-                                    if !matches.is_empty() {
-                                        return Err(ParserError::MethodAlreadyExists(function_name.clone().unwrap()));
-                                    }
-                                }
-                                Statement::Use(expression) => {
-                                    statement = Statement::UseTrait(expression.clone())
-                                },
-                                Statement::Property(Property { name: property_name, .. }) => {
-                                    let matches: Vec<Statement> = body
-                                        .clone()
-                                        .into_iter()
-                                        .filter(|statement| match statement {
-                                            Statement::Property(property) => property.name == *property_name,
-                                            _ => false,
-                                        })
-                                        .collect();
-
-                                    if !matches.is_empty() {
-                                        return Err(ParserError::PropertyAlreadyExists(property_name.clone()));
-                                    }
-                                }
-                                _ => return Err(ParserError::UnexpectedStatement(statement)),
-                            };
+                function.add_flag(flag_type)
+            }
+            Statement::Class(ref mut class) => {
+                if matches!(flag_type, Flag::Final) && class.has_flag(Flag::Abstract) {
+                    return Err(ParserError::FlagNotAllowed(flag_type, "abstract classes.".to_owned()));
+                }
 
-                            body.push(statement);
-                        }
-                    }
+                if matches!(flag_type, Flag::Abstract) && class.has_flag(Flag::Final) {
+                    return Err(ParserError::FlagNotAllowed(flag_type, "final classes.".to_owned()));
                 }
 
-                Statement::Class(Class::new(name.slice.to_owned(), implements, extends, body, Vec::new()))
+                if matches!(flag_type, Flag::Readonly) {
+                    Self::validate_readonly_class(class)?;
+                }
+
+                class.add_flag(flag_type)
             }
-            TokenType::Function => {
-                let identifier = self.expect_token(TokenType::Identifier, "")?;
+            Statement::Property(ref mut property) => {
+                if flag_type == Flag::Final || flag_type == Flag::Abstract {
+                    return Err(ParserError::FlagNotAllowed(flag_type, "properties".to_owned()));
+                }
 
-                self.expect_left_paren()?;
+                if property.has_flag(flag_type) {
+                    return Err(ParserError::DuplicateFlag(flag_type));
+                }
 
-                let mut parameters: Vec<FunctionParameter> = Vec::new();
+                if flag_type.is_visibility_flag() && property.has_visiblity_flag() {
+                    return Err(ParserError::FlagNotAllowed(flag_type, "properties with existing visiblity flags".to_owned()));
+                }
 
-                loop {
-                    let mut next = self.lexer.next();
+                if flag_type == Flag::Readonly && !property.is_typed() {
+                    return Err(ParserError::ReadonlyPropertyWithoutType(property.name.clone()));
+                }
 
-                    match next {
-                        // break when finding a ), no more parameters
-                        Some(Token {
-                            kind: TokenType::RightParen, ..
-                        }) => break,
-                        // consume trailing commas..
-                        Some(Token { kind: TokenType::Comma, .. }) => {
-                            next = self.lexer.next();
-                        }
-                        Some(Token {
-                            kind: TokenType::Identifier | TokenType::NullableIdentifier | TokenType::Variable,
-                            ..
-                        }) => (),
-                        None => return Err(ParserError::UnexpectedEndOfFile),
-                        _ => {
-                            let t = next.unwrap();
+                if (flag_type == Flag::Readonly && property.has_flag(Flag::Static)) || (flag_type == Flag::Static && property.has_flag(Flag::Readonly)) {
+                    return Err(ParserError::FlagNotAllowed(flag_type, "static readonly properties".to_owned()));
+                }
 
-                            return Err(ParserError::UnexpectedToken(t.kind, t.slice));
-                        }
-                    }
+                property.add_flag(flag_type)
+            }
+            Statement::ClassConst(ref mut constant) => {
+                if matches!(flag_type, Flag::Abstract | Flag::Static | Flag::Readonly) {
+                    return Err(ParserError::FlagNotAllowed(flag_type, "constants".to_owned()));
+                }
 
-                    let mut name = String::new();
-                    let mut type_hint = None;
+                if constant.has_flag(flag_type) {
+                    return Err(ParserError::DuplicateFlag(flag_type));
+                }
 
-                    match next {
-                        Some(
-                            t @ Token {
-                                kind: TokenType::Identifier | TokenType::NullableIdentifier, ..
-                            }
-                        ) => type_hint = Some(t.slice.to_string()),
-                        Some(t @ Token { kind: TokenType::Variable, .. }) => {
-                            let mut buffer: String = t.slice.to_string();
-                            buffer.remove(0);
+                if flag_type.is_visibility_flag() && constant.has_visiblity_flag() {
+                    return Err(ParserError::FlagNotAllowed(flag_type, "constants with existing visiblity flags".to_owned()));
+                }
 
-                            name = buffer;
-                        }
-                        None => return Err(ParserError::UnexpectedEndOfFile),
-                        _ => return Err(ParserError::Unknown),
-                    }
+                constant.add_flag(flag_type)
+            }
+            _ => return Err(ParserError::Unknown),
+        }
 
-                    if type_hint.is_some() {
-                        let variable = self.expect_token(TokenType::Variable, "")?;
+        Ok(statement)
+    }
 
-                        let mut buffer: String = variable.slice.to_string();
-                        buffer.remove(0);
+    fn parse_attributed_statement(&mut self) -> Result<Statement, ParserError<'p>> {
+        let attributes = self.parse_attribute_groups()?;
 
-                        name = buffer;
-                    }
+        let statement = match self.lexer.next() {
+            Some(t) => self.match_token(t)?,
+            None => return Err(ParserError::UnexpectedEndOfFile),
+        };
 
-                    let next = self.lexer.peek();
-                    let mut default = None;
+        Ok(match statement {
+            Statement::Function(mut function) => {
+                function.attributes = attributes;
 
-                    if matches!(next, Some(Token { kind: TokenType::Equals, .. })) {
-                        self.lexer.next();
-                        default = Some(self.parse_expression(0, None)?);
-                    }
+                Statement::Function(function)
+            },
+            Statement::Class(mut class) => {
+                class.attributes = attributes;
+
+                Statement::Class(class)
+            },
+            Statement::Property(mut property) => {
+                property.attributes = attributes;
+
+                Statement::Property(property)
+            },
+            Statement::ClassConst(mut constant) => {
+                constant.attributes = attributes;
+
+                Statement::ClassConst(constant)
+            },
+            // attributes are only allowed on class constants
+            Statement::Const(constants) => {
+                let mut constant = ClassConst::new(constants, None, Vec::new());
+
+                constant.attributes = attributes;
+
+                Statement::ClassConst(constant)
+            },
+            _ => return Err(ParserError::UnexpectedStatement(Box::new(statement))),
+        })
+    }
+
+    fn parse_interface(&mut self) -> Result<Statement, ParserError<'p>> {
+        let name = self.expect_token(TokenType::Identifier, "")?;
+        let mut extends = Vec::new();
+
+        if matches!(self.lexer.peek(), Some(Token { kind: TokenType::Extends, .. })) {
+            self.lexer.next();
+
+            loop {
+                let identifier = self.expect_token(TokenType::Identifier, "")?;
+
+                extends.push(identifier.slice.to_string());
+
+                if !matches!(self.lexer.peek(), Some(Token { kind: TokenType::Comma, .. })) {
+                    break;
+                }
+
+                self.lexer.next();
+            }
+        }
+
+        self.expect_left_brace()?;
+
+        let mut body: Vec<Statement> = Vec::new();
+
+        loop {
+            let next = self.lexer.next();
+
+            match next {
+                Some(Token {
+                    kind: TokenType::RightBrace, ..
+                }) => break,
+                None => return Err(ParserError::UnexpectedEndOfFile),
+                _ => {
+                    let statement = self.match_member(next.unwrap())?;
+
+                    match &statement {
+                        Statement::Function(Function { name: function_name, body: Some(..), .. }) => {
+                            return Err(ParserError::InterfaceMethodWithBody(function_name.clone().unwrap_or_default()));
+                        }
+                        Statement::Function(..) | Statement::ClassConst(..) => (),
+                        _ => return Err(ParserError::UnexpectedStatement(Box::new(statement))),
+                    };
+
+                    body.push(statement);
+                }
+            }
+        }
+
+        Ok(Statement::Interface(Interface::new(name.slice.to_owned(), extends, body)))
+    }
+
+    fn parse_function(&mut self) -> Result<Statement, ParserError<'p>> {
+        let by_ref = self.consume_by_ref();
+        let identifier = self.expect_token(TokenType::Identifier, "")?;
+        let Signature { parameters, return_type_hint, .. } = self.parse_signature(false)?;
+
+        let body = match self.lexer.next() {
+            // abstract and interface methods, e.g. `abstract public function handle(): void;`
+            Some(Token { kind: TokenType::SemiColon, .. }) => None,
+            Some(Token { kind: TokenType::LeftBrace, .. }) => Some(self.parse_block(TokenType::RightBrace)?),
+            Some(t) => {
+                return Err(ParserError::ExpectedToken {
+                    expected_type: TokenType::LeftBrace,
+                    expected_slice: "{",
+                    got_type: t.kind,
+                    got_slice: t.slice,
+                })
+            },
+            None => return Err(ParserError::UnexpectedEndOfFile),
+        };
+
+        Self::validate_promoted_parameters(Some(identifier.slice), body.is_some(), &parameters)?;
+
+        let mut function = Function::new(Some(identifier.slice.to_owned()), parameters, body, return_type_hint, Vec::new(), None);
+
+        function.by_ref = by_ref;
+
+        Ok(Statement::Function(function))
+    }
+
+    fn parse_anonymous_class(&mut self) -> Result<Expression, ParserError<'p>> {
+        let mut attributes = Vec::new();
+
+        if matches!(self.lexer.peek(), Some(Token { kind: TokenType::Attribute, .. })) {
+            self.lexer.next();
+
+            attributes = self.parse_attribute_groups()?;
+        }
+
+        let readonly = matches!(self.lexer.peek(), Some(Token { kind: TokenType::Readonly, .. }));
+
+        if readonly {
+            self.lexer.next();
+        }
+
+        self.expect_token(TokenType::Class, "class")?;
+
+        let mut args = Vec::new();
+
+        if matches!(self.lexer.peek(), Some(Token { kind: TokenType::LeftParen, .. })) {
+            self.lexer.next();
+
+            args = self.parse_arguments()?;
+        }
+
+        let mut class = self.parse_class(None)?;
+
+        class.attributes = attributes;
+
+        if readonly {
+            Self::validate_readonly_class(&class)?;
+
+            class.add_flag(Flag::Readonly);
+        }
+
+        Self::validate_class(&class)?;
+
+        Ok(Expression::New {
+            class: Box::new(Expression::AnonymousClass(Box::new(class))),
+            args,
+        })
+    }
+
+    fn parse_new(&mut self) -> Result<Expression, ParserError<'p>> {
+        let mut class = self.parse_expression(0, None)?;
+        let mut args = Vec::new();
+
+        match class {
+            Expression::Identifier(..) => (),
+            Expression::Call { target, args: ArgumentList::Arguments(call_args) } => {
+                class = *target.clone();
+                
+                for arg in call_args {
+                    args.push(arg);
+                }
+            },
+            _ => return Err(ParserError::UnexpectedExpression(Box::new(class)))
+        };
+
+        Ok(Expression::New {
+            class: Box::new(class),
+            args,
+        })
+    }
+
+    fn parse_attributed_closure(&mut self, bp: u8) -> Result<Expression, ParserError<'p>> {
+        let attributes = self.parse_attribute_groups()?;
+        let mut expression = self.parse_expression(bp, None)?;
+
+        match expression {
+            Expression::Closure(ref mut function) => function.attributes = attributes,
+            _ => return Err(ParserError::UnexpectedExpression(Box::new(expression))),
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_static_expression(&mut self) -> Result<Expression, ParserError<'p>> {
+        let mut expression = self.parse_expression(0, None)?;
+
+        match expression {
+            Expression::Closure(ref mut function) => {
+                if function.has_flags() {
+                    return Err(ParserError::CanOnlyHaveFlag(Flag::Static, "Anonymous functions".to_owned()))
+                }
+
+                function.add_flag(Flag::Static);
+            },
+            _ => {
+                return Err(ParserError::UnexpectedExpression(Box::new(expression)))
+            }
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_arrow_function(&mut self) -> Result<Expression, ParserError<'p>> {
+        let by_ref = self.consume_by_ref();
+        let Signature { parameters, return_type_hint, .. } = self.parse_signature(false)?;
+
+        Self::validate_promoted_parameters(None, true, &parameters)?;
+
+        self.expect_token(TokenType::DoubleArrow, "=>")?;
+
+        let expression = self.parse_expression(0, None)?;
+
+        let mut function = Function::new(
+            None,
+            parameters,
+            Some(vec![Statement::Expression(expression)]),
+            return_type_hint,
+            Vec::new(),
+            Some(ClosureType::Short)
+        );
+
+        function.by_ref = by_ref;
+
+        Ok(Expression::Closure(Box::new(function)))
+    }
+
+    fn parse_closure(&mut self) -> Result<Expression, ParserError<'p>> {
+        let by_ref = self.consume_by_ref();
+        let Signature { parameters, uses, return_type_hint } = self.parse_signature(true)?;
+
+        Self::validate_promoted_parameters(None, true, &parameters)?;
+
+        self.expect_left_brace()?;
+
+        let body = self.parse_block(TokenType::RightBrace)?;
+
+        let mut function = Function::new(None, parameters, Some(body), return_type_hint, Vec::new(), Some(ClosureType::Long));
+
+        function.uses = uses;
+        function.by_ref = by_ref;
+
+        Ok(Expression::Closure(Box::new(function)))
+    }
+
+    fn parse_identifier_expression(&mut self, next: Token<'p>) -> Result<Expression, ParserError<'p>> {
+        Ok(match self.lexer.peek_nth(0) {
+            Some(Token {
+                kind: TokenType::Variable,
+                slice,
+                ..
+            }) => {
+                let mut buffer = slice.to_string();
+                // remove the $
+                buffer.remove(0);
+
+                self.lexer.next();
+
+                Expression::TypedVariable(next.slice.to_owned(), buffer)
+            }
+            _ => match MagicConst::from_name(next.slice) {
+                Some(magic_const) => Expression::MagicConst(magic_const),
+                None => Expression::Identifier(next.slice.to_owned()),
+            },
+        })
+    }
+
+    fn parse_empty_or_eval(&mut self, next: Token<'p>) -> Result<Expression, ParserError<'p>> {
+        self.expect_left_paren()?;
+
+        let expression = Box::new(self.parse_expression(0, None)?);
+
+        self.expect_right_paren()?;
+
+        Ok(if next.kind == TokenType::Empty {
+            Expression::Empty(expression)
+        } else {
+            Expression::Eval(expression)
+        })
+    }
+
+    fn parse_exit(&mut self) -> Result<Expression, ParserError<'p>> {
+        let mut status = None;
+
+        if matches!(self.lexer.peek(), Some(Token { kind: TokenType::LeftParen, .. })) {
+            self.lexer.next();
+
+            if !matches!(self.lexer.peek(), Some(Token { kind: TokenType::RightParen, .. })) {
+                status = Some(Box::new(self.parse_expression(0, None)?));
+            }
+
+            self.expect_right_paren()?;
+        }
+
+        Ok(Expression::Exit(status))
+    }
+
+    fn parse_prefix_construct(&mut self, next: Token<'p>) -> Result<Expression, ParserError<'p>> {
+        let kind = next.kind;
+
+        let ((), rbp) = BindingPower::prefix(kind).ok_or(ParserError::Unknown)?;
+
+        let rhs = Box::new(self.parse_expression(rbp, None)?);
+
+        Ok(match kind {
+            TokenType::At => Expression::ErrorSuppress(rhs),
+            TokenType::Clone => Expression::Clone(rhs),
+            TokenType::Print => Expression::Print(rhs),
+            TokenType::Include => Expression::Include(rhs),
+            TokenType::IncludeOnce => Expression::IncludeOnce(rhs),
+            TokenType::Require => Expression::Require(rhs),
+            TokenType::RequireOnce => Expression::RequireOnce(rhs),
+            _ => return Err(ParserError::UnexpectedToken(kind, next.slice)),
+        })
+    }
+
+    /// Parses the operand of `-`, `!` or `~`.
+    fn parse_unary(&mut self, kind: TokenType) -> Result<Expression, ParserError<'p>> {
+        let ((), rbp) = BindingPower::prefix(kind).ok_or(ParserError::Unknown)?;
+        let rhs = Box::new(self.parse_expression(rbp, None)?);
+
+        Ok(match kind {
+            TokenType::Minus => Expression::Unary(rhs),
+            TokenType::Not => Expression::Negate(rhs),
+            _ => Expression::BitwiseNot(rhs),
+        })
+    }
+
+    fn parse_yield(&mut self) -> Result<Expression, ParserError<'p>> {
+        let ((), rbp) = BindingPower::prefix(TokenType::Yield).ok_or(ParserError::Unknown)?;
+
+        Ok(match self.lexer.peek() {
+            Some(Token { kind: TokenType::Identifier, slice, .. }) if slice.eq_ignore_ascii_case("from") => {
+                self.lexer.next();
+
+                let rhs = self.parse_expression(rbp, None)?;
+
+                Expression::YieldFrom(Box::new(rhs))
+            },
+            Some(Token { kind: TokenType::SemiColon | TokenType::RightParen | TokenType::RightBracket | TokenType::Comma, .. }) => Expression::Yield { key: None, value: None },
+            _ => {
+                let mut key = None;
+                let mut value = self.parse_expression(rbp, None)?;
+
+                if matches!(self.lexer.peek(), Some(Token { kind: TokenType::DoubleArrow, .. })) {
+                    self.lexer.next();
+
+                    key = Some(Box::new(value));
+                    value = self.parse_expression(rbp, None)?;
+                }
+
+                Expression::Yield { key, value: Some(Box::new(value)) }
+            }
+        })
+    }
+
+    /// Applies the postfix and infix operators that bind tighter than `bp` to `lhs`.
+    fn parse_operators(&mut self, mut lhs: Expression, bp: u8) -> Result<Expression, ParserError<'p>> {
+        loop {
+            let next = self.lexer.peek();
+
+            if next.is_none() {
+                return Err(ParserError::UnexpectedEndOfFile);
+            }
+
+            let op = next.unwrap();
+
+            if let Some((lbp, _)) = BindingPower::postfix(op.kind) {
+                if lbp < bp {
+                    break;
+                }
+
+                let op = self.lexer.next().unwrap();
+
+                lhs = self.parse_postfix(lhs, op)?;
+
+                continue;
+            } else if let Some((lbp, rbp)) = BindingPower::infix(op.kind) {
+                if lbp < bp {
+                    break;
+                }
+
+                let op = self.lexer.next().unwrap();
+
+                // reference assignment, e.g. `$a = &$b`
+                if op.kind == TokenType::Equals && self.consume_by_ref() {
+                    let rhs = self.parse_expression(rbp, None)?;
+
+                    lhs = Expression::AssignRef(Box::new(lhs), Box::new(rhs));
+
+                    continue;
+                }
+
+                let rhs = self.parse_expression(rbp, None)?;
+
+                lhs = Expression::make_infix(lhs, &op.kind, rhs).ok_or(ParserError::UnexpectedToken(op.kind, op.slice))?;
+
+                continue;
+            }
+
+            break;
+        }
+
+        Ok(lhs)
+    }
+
+    /// Applies a single postfix operator, such as a call or a property access, to `lhs`.
+    fn parse_postfix(&mut self, lhs: Expression, op: Token<'p>) -> Result<Expression, ParserError<'p>> {
+        Ok(match op.kind {
+            kind @ (TokenType::Arrow | TokenType::NullsafeArrow) => {
+                let member = self.parse_member_name()?;
+                let nullsafe = kind == TokenType::NullsafeArrow;
+
+                if matches!(self.lexer.peek(), Some(Token { kind: TokenType::LeftParen, .. })) {
+                    self.lexer.next();
+
+                    let target = Box::new(lhs);
+                    let method = Box::new(member);
+                    let args = self.parse_argument_list()?;
+
+                    match args {
+                        // PHP rejects `$a?->b(...)`, as the closure could not be null
+                        ArgumentList::FirstClassCallable if nullsafe => return Err(ParserError::UnexpectedToken(TokenType::Ellipsis, "...")),
+                        ArgumentList::Arguments(args) if nullsafe => Expression::NullsafeMethodCall { target, method, args },
+                        args => Expression::MethodCall { target, method, args },
+                    }
+                } else if nullsafe {
+                    Expression::NullsafePropertyAccess(Box::new(lhs), Box::new(member))
+                } else {
+                    Expression::PropertyAccess(Box::new(lhs), Box::new(member))
+                }
+            },
+            TokenType::LeftBracket => {
+                let next = self.lexer.next();
+
+                let expression = match next {
+                    Some(Token {
+                        kind: TokenType::RightBracket, ..
+                    }) => None,
+                    None => return Err(ParserError::UnexpectedEndOfFile),
+                    _ => {
+                        let index = self.parse_expression(0, next)?;
+
+                        self.expect_token(TokenType::RightBracket, "]")?;
+
+                        Some(Box::new(index))
+                    }
+                };
+
+                Expression::ArrayAccess(Box::new(lhs), expression)
+            }
+            TokenType::LeftParen => {
+                let args = self.parse_argument_list()?;
+
+                Expression::Call {
+                    target: Box::new(lhs),
+                    args,
+                }
+            }
+            _ => return Err(ParserError::UnexpectedToken(op.kind, op.slice)),
+        })
+    }
+
+    fn expect_token(&mut self, kind: TokenType, slice: &'p str) -> Result<Token<'p>, ParserError<'p>> {
+        let next = self.lexer.next();
+
+        if let Some(token) = next {
+            if token.kind != kind {
+                Err(ParserError::ExpectedToken {
+                    expected_type: kind,
+                    expected_slice: slice,
+                    got_type: token.kind,
+                    got_slice: token.slice,
+                })
+            } else {
+                Ok(token)
+            }
+        } else {
+            Err(ParserError::UnexpectedEndOfFile)
+        }
+    }
+
+    fn parse_expression(&mut self, bp: u8, maybe_token: Option<Token<'p>>) -> Result<Expression, ParserError<'p>> {
+        self.nested(|parser| parser.parse_expression_inner(bp, maybe_token))
+    }
+
+    fn parse_expression_inner(&mut self, bp: u8, maybe_token: Option<Token<'p>>) -> Result<Expression, ParserError<'p>> {
+        let next = if maybe_token.is_none() { self.lexer.next() } else { maybe_token };
+
+        if next.is_none() {
+            return Err(ParserError::UnexpectedEndOfFile);
+        }
+
+        let next = next.unwrap();
+
+        let lhs = match next.kind {
+            TokenType::New if matches!(self.lexer.peek(), Some(Token { kind: TokenType::Class | TokenType::Readonly | TokenType::Attribute, .. })) => self.parse_anonymous_class()?,
+            TokenType::New => self.parse_new()?,
+            TokenType::Attribute => self.parse_attributed_closure(bp)?,
+            TokenType::Static => self.parse_static_expression()?,
+            TokenType::ShortFunction => self.parse_arrow_function()?,
+            TokenType::Function => self.parse_closure()?,
+            TokenType::String => {
+                let mut buffer: String = next.slice.to_owned();
+                // remove the quotes
+                buffer.remove(0);
+                buffer.pop();
+
+                Expression::String(buffer)
+            }
+            TokenType::Integer => Self::parse_integer(next.slice)?,
+            TokenType::Float => Self::parse_float(next.slice)?,
+            TokenType::Variable => {
+                let mut buffer = next.slice.to_string();
+                // remove the $
+                buffer.remove(0);
+
+                Expression::Variable(buffer)
+            }
+            TokenType::True => Expression::from(true),
+            TokenType::False => Expression::from(false),
+            TokenType::Null => Expression::Null,
+            TokenType::LeftParen => {
+                let expression = self.parse_expression(0, None)?;
+
+                self.expect_right_paren()?;
+
+                expression
+            },
+            TokenType::LeftBracket if self.is_destructuring() => self.parse_list(TokenType::RightBracket)?,
+            TokenType::List => {
+                self.expect_left_paren()?;
+
+                self.parse_list(TokenType::RightParen)?
+            },
+            TokenType::LeftBracket => Expression::Array(self.parse_array_items(TokenType::RightBracket)?),
+            TokenType::Identifier if next.slice.eq_ignore_ascii_case("array") && matches!(self.lexer.peek(), Some(Token { kind: TokenType::LeftParen, .. })) => {
+                self.lexer.next();
+
+                Expression::Array(self.parse_array_items(TokenType::RightParen)?)
+            },
+            TokenType::Identifier | TokenType::NullableIdentifier => self.parse_identifier_expression(next)?,
+            TokenType::Isset => {
+                self.expect_left_paren()?;
+
+                Expression::Isset(self.parse_construct_arguments()?)
+            },
+            TokenType::Empty | TokenType::Eval => self.parse_empty_or_eval(next)?,
+            TokenType::Exit | TokenType::Die => self.parse_exit()?,
+            TokenType::At | TokenType::Clone | TokenType::Print | TokenType::Include | TokenType::IncludeOnce | TokenType::Require | TokenType::RequireOnce => self.parse_prefix_construct(next)?,
+            TokenType::Yield => self.parse_yield()?,
+            TokenType::Minus | TokenType::Not | TokenType::BitwiseNot => self.parse_unary(next.kind)?,
+            _ => return Err(ParserError::UnexpectedToken(next.kind, next.slice)),
+        };
+
+        self.parse_operators(lhs, bp)
+    }
+
+    /// Parses the parameter list and return type shared by functions, methods,
+    /// closures and arrow functions. Closures can also capture variables with
+    /// a `use` clause, which comes between the two.
+    fn parse_signature(&mut self, closure: bool) -> Result<Signature, ParserError<'p>> {
+        self.expect_left_paren()?;
+
+        let mut parameters: Vec<FunctionParameter> = Vec::new();
+
+        loop {
+            let next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
+
+            // an empty list, or a trailing comma
+            if next.kind == TokenType::RightParen {
+                break;
+            }
+
+            if let Some(FunctionParameter { variadic: true, name, .. }) = parameters.last() {
+                return Err(ParserError::VariadicParameterMustBeLast(name.clone()));
+            }
+
+            parameters.push(self.parse_parameter(next)?);
+
+            match self.lexer.next() {
+                Some(Token { kind: TokenType::Comma, .. }) => (),
+                Some(Token { kind: TokenType::RightParen, .. }) => break,
+                Some(t) => return Err(ParserError::UnexpectedToken(t.kind, t.slice)),
+                None => return Err(ParserError::UnexpectedEndOfFile),
+            }
+        }
+
+        let mut uses = Vec::new();
+
+        if closure && matches!(self.lexer.peek(), Some(Token { kind: TokenType::Use, .. })) {
+            self.lexer.next();
+
+            uses = self.parse_closure_uses(&parameters)?;
+        }
+
+        let mut return_type_hint = None;
+
+        if matches!(self.lexer.peek(), Some(Token { kind: TokenType::Colon, .. })) {
+            self.lexer.next();
+
+            let next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
+
+            return_type_hint = Some(self.parse_type(next)?);
+        }
+
+        Ok(Signature { parameters, uses, return_type_hint })
+    }
+
+    fn parse_parameter(&mut self, token: Token<'p>) -> Result<FunctionParameter, ParserError<'p>> {
+        let mut next = token;
+        let mut flags = Vec::new();
+        let mut attributes = Vec::new();
+
+        if next.kind == TokenType::Attribute {
+            attributes = self.parse_attribute_groups()?;
+            next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
+        }
+
+        // promoted constructor parameters, e.g. `private Foo $foo`
+        loop {
+            let flag = match next.kind {
+                TokenType::Public => Flag::Public,
+                TokenType::Protected => Flag::Protected,
+                TokenType::Private => Flag::Private,
+                TokenType::Readonly => Flag::Readonly,
+                _ => break,
+            };
+
+            if flags.contains(&flag) {
+                return Err(ParserError::DuplicateFlag(flag));
+            }
+
+            if flag.is_visibility_flag() && flags.iter().any(Flag::is_visibility_flag) {
+                return Err(ParserError::FlagNotAllowed(flag, "parameters with existing visiblity flags".to_owned()));
+            }
+
+            flags.push(flag);
+            next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
+        }
+
+        let mut type_hint = None;
+
+        if Self::is_type_start(next.kind) {
+            type_hint = Some(self.parse_type(next)?);
+            next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
+        }
+
+        let by_ref = next.kind == TokenType::BitwiseAnd;
+
+        if by_ref {
+            next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
+        }
+
+        let variadic = next.kind == TokenType::Ellipsis;
+
+        if variadic {
+            next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
+        }
+
+        if next.kind != TokenType::Variable {
+            return Err(ParserError::ExpectedToken {
+                expected_type: TokenType::Variable,
+                expected_slice: "",
+                got_type: next.kind,
+                got_slice: next.slice,
+            });
+        }
+
+        let mut name: String = next.slice.to_string();
+        name.remove(0);
+
+        if flags.contains(&Flag::Readonly) && type_hint.is_none() {
+            return Err(ParserError::ReadonlyPropertyWithoutType(name));
+        }
+
+        let mut default = None;
+
+        if let Some(Token { kind: TokenType::Equals, .. }) = self.lexer.peek() {
+            if variadic {
+                return Err(ParserError::UnexpectedToken(TokenType::Equals, "="));
+            }
+
+            self.lexer.next();
+
+            default = Some(self.parse_expression(0, None)?);
+        }
+
+        let mut parameter = FunctionParameter::new(name, type_hint, default);
+
+        parameter.by_ref = by_ref;
+        parameter.variadic = variadic;
+        parameter.flags = flags;
+        parameter.attributes = attributes;
+
+        Ok(parameter)
+    }
+
+    fn validate_method_body(function: &Function) -> Result<(), ParserError<'p>> {
+        let name = function.name.clone().unwrap_or_default();
+
+        match (function.has_flag(Flag::Abstract), &function.body) {
+            (true, Some(..)) => Err(ParserError::AbstractMethodWithBody(name)),
+            (false, None) => Err(ParserError::MethodWithoutBody(name)),
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_class(class: &Class) -> Result<(), ParserError<'p>> {
+        if !class.has_flag(Flag::Abstract) && class.methods().any(|method| method.has_flag(Flag::Abstract)) {
+            return Err(ParserError::ClassMustBeAbstract(class.name().unwrap_or("class@anonymous").to_owned()));
+        }
+
+        if let Some(parameter) = class.promoted_parameters().find(|parameter| class.properties().any(|property| property.name == parameter.name)) {
+            return Err(ParserError::PropertyAlreadyExists(parameter.name.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Every property of a readonly class is readonly, so each one needs a
+    /// type, including those promoted in its constructor.
+    fn validate_readonly_class(class: &Class) -> Result<(), ParserError<'p>> {
+        if let Some(property) = class.properties().find(|property| !property.is_typed()) {
+            return Err(ParserError::ReadonlyPropertyWithoutType(property.name.clone()));
+        }
+
+        if let Some(parameter) = class.promoted_parameters().find(|parameter| parameter.type_hint.is_none()) {
+            return Err(ParserError::ReadonlyPropertyWithoutType(parameter.name.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Promotion is only allowed in a constructor with a body, so never in
+    /// abstract or interface constructors.
+    fn validate_promoted_parameters(function_name: Option<&str>, has_body: bool, parameters: &[FunctionParameter]) -> Result<(), ParserError<'p>> {
+        let parameter = match parameters.iter().find(|parameter| parameter.is_promoted()) {
+            Some(parameter) => parameter,
+            None => return Ok(()),
+        };
+
+        if !matches!(function_name, Some(name) if name.eq_ignore_ascii_case("__construct")) {
+            return Err(ParserError::PromotedPropertyOutsideConstructor(parameter.name.clone()));
+        }
+
+        if !has_body {
+            return Err(ParserError::PromotedPropertyInAbstractConstructor(parameter.name.clone()));
+        }
+
+        Ok(())
+    }
+
+    fn parse_class(&mut self, name: Option<String>) -> Result<Class, ParserError<'p>> {
+        let mut implements = Vec::new();
+        let mut extends = String::new();
+
+        'outer: loop {
+            let next = self.lexer.next();
+
+            match next {
+                Some(Token { kind: TokenType::Extends, .. }) => {
+                    if !implements.is_empty() {
+                        let t = next.unwrap();
+
+                        return Err(ParserError::UnexpectedToken(t.kind, t.slice));
+                    }
+
+                    let identifier = self.expect_token(TokenType::Identifier, "")?;
+
+                    extends = identifier.slice.to_string();
+                }
+                Some(Token {
+                    kind: TokenType::Implements, ..
+                }) => {
+                    let identifier = self.expect_token(TokenType::Identifier, "")?;
+
+                    implements.push(identifier.slice.to_string());
+
+                    loop {
+                        let next = self.lexer.next();
+
+                        match next {
+                            Some(Token {
+                                kind: TokenType::Identifier, ..
+                            }) => {
+                                implements.push(next.unwrap().slice.to_string());
+                            }
+                            Some(Token { kind: TokenType::Comma, .. }) => {
+                                let identifier = self.expect_token(TokenType::Identifier, "")?;
+
+                                implements.push(identifier.slice.to_string());
+                            }
+                            Some(Token {
+                                kind: TokenType::LeftBrace, ..
+                            }) => {
+                                if !implements.is_empty() {
+                                    break 'outer;
+                                }
+
+                                continue;
+                            }
+                            None => return Err(ParserError::UnexpectedEndOfFile),
+                            _ => {
+                                let t = next.unwrap();
+
+                                return Err(ParserError::UnexpectedToken(t.kind, t.slice));
+                            }
+                        }
+                    }
+                }
+                Some(Token {
+                    kind: TokenType::LeftBrace, ..
+                }) => break,
+                _ => return Err(ParserError::Unknown),
+            }
+        }
+
+        let mut body: Vec<Statement> = Vec::new();
+
+        loop {
+            let next = self.lexer.next();
+
+            match next {
+                Some(Token {
+                    kind: TokenType::RightBrace, ..
+                }) => break,
+                None => return Err(ParserError::UnexpectedEndOfFile),
+                _ => {
+                    let mut statement = self.match_member(next.unwrap())?;
+
+                    match &statement {
+                        Statement::Function(function @ Function { name: function_name, .. }) => {
+                            let matches: Vec<Statement> = body
+                                .clone()
+                                .into_iter()
+                                .filter(|statement| match statement {
+                                    Statement::Function(function) => function.name == *function_name,
+                                    _ => false,
+                                })
+                                .collect();
+
+                            if !matches.is_empty() {
+                                return Err(ParserError::MethodAlreadyExists(function_name.clone().unwrap()));
+                            }
+
+                            Self::validate_method_body(function)?;
+                        }
+                        Statement::Use(expression) => {
+                            statement = Statement::UseTrait(expression.clone())
+                        },
+                        Statement::Property(Property { name: property_name, .. }) => {
+                            let matches: Vec<Statement> = body
+                                .clone()
+                                .into_iter()
+                                .filter(|statement| match statement {
+                                    Statement::Property(property) => property.name == *property_name,
+                                    _ => false,
+                                })
+                                .collect();
+
+                            if !matches.is_empty() {
+                                return Err(ParserError::PropertyAlreadyExists(property_name.clone()));
+                            }
+                        }
+                        Statement::ClassConst(class_const) => {
+                            for constant in &class_const.constants {
+                                let already_exists = body.iter().any(|statement| match statement {
+                                    Statement::ClassConst(existing) => existing.has_constant(&constant.name),
+                                    _ => false,
+                                });
+
+                                if already_exists || class_const.constants.iter().filter(|other| other.name == constant.name).count() > 1 {
+                                    return Err(ParserError::ConstantAlreadyExists(constant.name.clone()));
+                                }
+                            }
+                        }
+                        _ => return Err(ParserError::UnexpectedStatement(Box::new(statement))),
+                    };
+
+                    body.push(statement);
+                }
+            }
+        }
+
+        Ok(Class::new(name, implements, extends, body, Vec::new()))
+    }
+
+    fn parse_static_vars(&mut self, token: Token<'p>) -> Result<Vec<StaticVar>, ParserError<'p>> {
+        let mut vars = Vec::new();
+        let mut next = token;
+
+        loop {
+            if next.kind != TokenType::Variable {
+                return Err(ParserError::UnexpectedToken(next.kind, next.slice));
+            }
+
+            let mut default = None;
+
+            if matches!(self.lexer.peek(), Some(Token { kind: TokenType::Equals, .. })) {
+                self.lexer.next();
+
+                default = Some(self.parse_expression(0, None)?);
+            }
+
+            vars.push(StaticVar::new(next.slice[1..].to_owned(), default));
+
+            match self.lexer.next() {
+                Some(Token { kind: TokenType::Comma, .. }) => next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?,
+                Some(Token { kind: TokenType::SemiColon, .. }) => break,
+                Some(token) => return Err(ParserError::UnexpectedToken(token.kind, token.slice)),
+                None => return Err(ParserError::UnexpectedEndOfFile),
+            }
+        }
+
+        Ok(vars)
+    }
+
+    /// Inside a class body, `static $count = 0;` declares a static property.
+    fn static_property(mut vars: Vec<StaticVar>) -> Result<Statement, ParserError<'p>> {
+        if vars.len() != 1 {
+            return Err(ParserError::UnexpectedStatement(Box::new(Statement::StaticVar(vars))));
+        }
+
+        let var = vars.remove(0);
+
+        Ok(Statement::Property(Property::new(var.name, vec![Flag::Static], None, var.default)))
+    }
+
+    fn parse_constants(&mut self) -> Result<Vec<Constant>, ParserError<'p>> {
+        let mut constants = Vec::new();
+
+        loop {
+            let name = match self.lexer.next() {
+                Some(t) if Self::is_identifier_like(t.slice) => t.slice.to_owned(),
+                Some(t) => return Err(ParserError::UnexpectedToken(t.kind, t.slice)),
+                None => return Err(ParserError::UnexpectedEndOfFile),
+            };
+
+            self.expect_token(TokenType::Equals, "=")?;
+
+            let value = self.parse_expression(0, None)?;
+
+            constants.push(Constant::new(name, value));
+
+            match self.lexer.next() {
+                Some(Token { kind: TokenType::Comma, .. }) => continue,
+                Some(Token { kind: TokenType::SemiColon, .. }) => break,
+                Some(t) => return Err(ParserError::UnexpectedToken(t.kind, t.slice)),
+                None => return Err(ParserError::UnexpectedEndOfFile),
+            }
+        }
+
+        Ok(constants)
+    }
+
+    /// Converts an integer literal following PHP's rules: `0x`, `0b`, `0o` and
+    /// leading-zero octal prefixes, `_` separators, and overflow into a float.
+    fn parse_integer(literal: &str) -> Result<Expression, ParserError<'p>> {
+        let literal_lower = literal.to_ascii_lowercase();
+
+        let (radix, digits) = if let Some(digits) = literal_lower.strip_prefix("0x") {
+            (16, Self::strip_separators(digits, 16))
+        } else if let Some(digits) = literal_lower.strip_prefix("0b") {
+            (2, Self::strip_separators(digits, 2))
+        } else if let Some(digits) = literal_lower.strip_prefix("0o") {
+            (8, Self::strip_separators(digits, 8))
+        } else if literal_lower.len() > 1 && literal_lower.starts_with('0') {
+            // the separator may follow the leading zero, e.g. `0_17`
+            (8, Self::strip_separators(&literal_lower, 10).map(|digits| digits[1..].to_owned()))
+        } else {
+            (10, Self::strip_separators(&literal_lower, 10))
+        };
+
+        let digits = digits.ok_or(ParserError::IntegerParserError)?;
+
+        // `from_str_radix` would accept a sign, which is never part of the literal
+        if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+            return Err(ParserError::IntegerParserError);
+        }
+
+        let raw = literal.to_owned();
+
+        match i64::from_str_radix(&digits, radix) {
+            Ok(value) => Ok(Expression::Integer { value, raw }),
+            // the digits are valid, so the only possible error is overflow
+            Err(_) if radix == 10 => Ok(Expression::Float { value: digits.parse::<f64>()?, raw }),
+            Err(_) => {
+                let value = digits.chars().filter_map(|c| c.to_digit(radix)).fold(0.0, |value, digit| value * radix as f64 + digit as f64);
+
+                Ok(Expression::Float { value, raw })
+            },
+        }
+    }
+
+    fn parse_float(literal: &str) -> Result<Expression, ParserError<'p>> {
+        let digits = Self::strip_separators(literal, 10).ok_or(ParserError::FloatParserError)?;
+
+        Ok(Expression::Float { value: digits.parse::<f64>()?, raw: literal.to_owned() })
+    }
+
+    /// Removes the `_` separators from a number, or returns `None` if one is
+    /// not between two digits of `radix`, e.g. in `1__0`, `1_` or `1_.5`.
+    fn strip_separators(digits: &str, radix: u32) -> Option<String> {
+        let chars: Vec<char> = digits.chars().collect();
+
+        for (i, c) in chars.iter().enumerate() {
+            if *c != '_' {
+                continue;
+            }
+
+            let before = i.checked_sub(1).and_then(|i| chars.get(i));
+            let after = chars.get(i + 1);
+
+            if !matches!((before, after), (Some(before), Some(after)) if before.is_digit(radix) && after.is_digit(radix)) {
+                return None;
+            }
+        }
+
+        Some(digits.replace('_', ""))
+    }
+
+    /// Parses the optional operand of `break`, `continue` or `return`, up to and including the `;`.
+    fn parse_optional_expression(&mut self) -> Result<Option<Expression>, ParserError<'p>> {
+        let expression = match self.lexer.peek() {
+            Some(Token { kind: TokenType::SemiColon, .. }) => None,
+            None => return Err(ParserError::UnexpectedEndOfFile),
+            _ => Some(self.parse_expression(0, None)?),
+        };
+
+        self.expect_token(TokenType::SemiColon, ";")?;
+
+        Ok(expression)
+    }
+
+    /// Parses statements up to and including the `end` token.
+    fn parse_block(&mut self, end: TokenType) -> Result<Vec<Statement>, ParserError<'p>> {
+        let mut body = Vec::new();
+
+        loop {
+            match self.lexer.next() {
+                Some(Token { kind, .. }) if kind == end => break,
+                Some(token) => body.push(self.match_statement(token)?),
+                None => return Err(ParserError::UnexpectedEndOfFile),
+            }
+        }
+
+        Ok(body)
+    }
+
+    /// Parses the comma separated operands of a construct such as `isset(` or
+    /// `unset(`, up to and including the closing `)`. At least one is required.
+    fn parse_construct_arguments(&mut self) -> Result<Vec<Expression>, ParserError<'p>> {
+        let mut expressions = Vec::new();
+
+        loop {
+            if let Some(&Token { kind: TokenType::RightParen, slice }) = self.lexer.peek() {
+                if expressions.is_empty() {
+                    return Err(ParserError::UnexpectedToken(TokenType::RightParen, slice));
+                }
+
+                self.lexer.next();
+
+                break;
+            }
+
+            expressions.push(self.parse_expression(0, None)?);
+
+            match self.lexer.next() {
+                Some(Token { kind: TokenType::Comma, .. }) => (),
+                Some(Token { kind: TokenType::RightParen, .. }) => break,
+                Some(token) => return Err(ParserError::UnexpectedToken(token.kind, token.slice)),
+                None => return Err(ParserError::UnexpectedEndOfFile),
+            }
+        }
+
+        Ok(expressions)
+    }
+
+    /// Parses the items of an array literal, after its opening `[` or `array(`.
+    fn parse_array_items(&mut self, close: TokenType) -> Result<Vec<ArrayItem>, ParserError<'p>> {
+        let mut items = Vec::new();
+
+        loop {
+            match self.lexer.peek() {
+                Some(Token { kind, .. }) if *kind == close => {
+                    self.lexer.next();
 
-                    parameters.push(FunctionParameter::new(name, type_hint, default))
-                }
+                    break;
+                },
+                Some(&Token { kind: TokenType::Comma, slice }) => return Err(ParserError::UnexpectedToken(TokenType::Comma, slice)),
+                None => return Err(ParserError::UnexpectedEndOfFile),
+                _ => (),
+            }
 
-                let mut return_type_hint = None;
-                let next = self.lexer.next();
+            let item = if matches!(self.lexer.peek(), Some(Token { kind: TokenType::Ellipsis, .. })) {
+                self.lexer.next();
 
-                if matches!(next, Some(Token { kind: TokenType::Colon, .. })) {
-                    let return_type_token = self.expect_token(TokenType::Identifier, "")?;
+                ArrayItem::spread(self.parse_expression(0, None)?)
+            } else {
+                let mut key = None;
+                let mut by_ref = self.consume_by_ref();
+                let mut value = self.parse_expression(0, None)?;
 
-                    return_type_hint = Some(return_type_token.slice.to_string());
+                if !by_ref && matches!(self.lexer.peek(), Some(Token { kind: TokenType::DoubleArrow, .. })) {
+                    self.lexer.next();
 
-                    self.expect_left_brace()?;
-                } else if next.is_some()
-                    && !matches!(
-                        next,
-                        Some(Token {
-                            kind: TokenType::LeftBrace,
-                            ..
-                        })
-                    )
-                {
-                    let next = next.unwrap();
-
-                    return Err(ParserError::ExpectedToken {
-                        expected_type: TokenType::LeftBrace,
-                        expected_slice: "{",
-                        got_type: next.kind,
-                        got_slice: next.slice,
-                    });
+                    key = Some(value);
+                    by_ref = self.consume_by_ref();
+                    value = self.parse_expression(0, None)?;
                 }
 
-                let mut body = Vec::new();
+                ArrayItem::new(key, value, by_ref)
+            };
 
-                loop {
-                    let next = self.lexer.next();
+            items.push(item);
 
-                    match next {
-                        Some(Token {
-                            kind: TokenType::RightBrace, ..
-                        }) => break,
-                        None => return Err(ParserError::UnexpectedEndOfFile),
-                        _ => {
-                            let statement = self.match_token(next.unwrap())?;
+            match self.lexer.next() {
+                Some(Token { kind: TokenType::Comma, .. }) => (),
+                Some(Token { kind, .. }) if kind == close => break,
+                Some(token) => return Err(ParserError::UnexpectedToken(token.kind, token.slice)),
+                None => return Err(ParserError::UnexpectedEndOfFile),
+            }
+        }
 
-                            body.push(statement);
-                        }
-                    }
-                }
+        Ok(items)
+    }
 
-                Statement::Function(Function::new(Some(identifier.slice.to_owned()), parameters, body, return_type_hint, Vec::new(), None))
+    /// Whether the `[` that was just consumed opens a destructuring pattern,
+    /// i.e. whether its matching `]` is followed by `=`.
+    fn is_destructuring(&self) -> bool {
+        let mut lexer = self.lexer.lookahead();
+        let mut depth = 0;
+
+        while let Some(token) = lexer.next() {
+            match token.kind {
+                TokenType::LeftBracket | TokenType::LeftParen | TokenType::LeftBrace => depth += 1,
+                TokenType::RightBracket | TokenType::RightParen | TokenType::RightBrace if depth > 0 => depth -= 1,
+                TokenType::RightBracket => return matches!(lexer.next(), Some(Token { kind: TokenType::Equals, .. })),
+                TokenType::RightParen | TokenType::RightBrace => return false,
+                _ => (),
             }
-            TokenType::String => {
-                let mut buffer: String = token.slice.to_string();
+        }
 
-                buffer.remove(0);
-                buffer.pop();
+        false
+    }
 
-                self.lexer.next();
+    /// Parses the items of a destructuring pattern, after its opening `[` or `list(`.
+    fn parse_list(&mut self, close: TokenType) -> Result<Expression, ParserError<'p>> {
+        let mut items = Vec::new();
+
+        loop {
+            match self.lexer.peek() {
+                Some(Token { kind, .. }) if *kind == close => {
+                    self.lexer.next();
 
-                Statement::Expression(Expression::String(buffer))
+                    break;
+                },
+                Some(Token { kind: TokenType::Comma, .. }) => {
+                    self.lexer.next();
+                    items.push(None);
+
+                    continue;
+                },
+                None => return Err(ParserError::UnexpectedEndOfFile),
+                _ => (),
             }
-            TokenType::Integer => Statement::Expression(Expression::Integer(token.slice.parse::<i64>()?)),
-            TokenType::Float => Statement::Expression(Expression::Float(token.slice.parse::<f64>()?)),
-            _ => {
-                let expression = self.parse_expression(0, Some(token))?;
 
-                self.expect_token(TokenType::SemiColon, ";")?;
+            let mut key = None;
+            let mut by_ref = self.consume_by_ref();
+            let mut value = self.parse_list_target()?;
+
+            if !by_ref && matches!(self.lexer.peek(), Some(Token { kind: TokenType::DoubleArrow, .. })) {
+                self.lexer.next();
 
-                Statement::Expression(expression)
+                key = Some(value);
+                by_ref = self.consume_by_ref();
+                value = self.parse_list_target()?;
             }
-        })
-    }
 
-    fn expect_token(&mut self, kind: TokenType, slice: &'p str) -> Result<Token<'p>, ParserError<'p>> {
-        let next = self.lexer.next();
+            items.push(Some(ListItem::new(key, value, by_ref)));
 
-        if let Some(token) = next {
-            if token.kind != kind {
-                Err(ParserError::ExpectedToken {
-                    expected_type: kind,
-                    expected_slice: slice,
-                    got_type: token.kind,
-                    got_slice: token.slice,
-                })
-            } else {
-                Ok(token)
+            match self.lexer.next() {
+                Some(Token { kind: TokenType::Comma, .. }) => (),
+                Some(Token { kind, .. }) if kind == close => break,
+                Some(token) => return Err(ParserError::UnexpectedToken(token.kind, token.slice)),
+                None => return Err(ParserError::UnexpectedEndOfFile),
             }
-        } else {
-            Err(ParserError::UnexpectedEndOfFile)
         }
-    }
-
-    fn parse_expression<'n>(&mut self, bp: u8, maybe_token: Option<Token>) -> Result<Expression, ParserError<'p>> {
-        let next = if maybe_token.is_none() { self.lexer.next() } else { maybe_token };
 
-        if next.is_none() {
-            return Err(ParserError::UnexpectedEndOfFile);
+        // skipped items alone don't assign anything, e.g. `[, ,] = $a`
+        if items.iter().all(Option::is_none) {
+            return Err(ParserError::EmptyList);
         }
 
-        let next = next.unwrap();
-
-        let mut lhs = match next.kind {
-            TokenType::New => {
-                let mut class = self.parse_expression(0, None)?;
-                let mut args = Vec::new();
+        Ok(Expression::List(items))
+    }
 
-                match class {
-                    Expression::Identifier(..) => (),
-                    Expression::Call { target, args: call_args } => {
-                        class = *target.clone();
-                        
-                        for arg in call_args {
-                            args.push(arg);
-                        }
-                    },
-                    _ => return Err(ParserError::UnexpectedExpression(class))
-                };
+    fn parse_list_target(&mut self) -> Result<Expression, ParserError<'p>> {
+        match self.lexer.peek() {
+            Some(Token { kind: TokenType::LeftBracket, .. }) => {
+                self.lexer.next();
 
-                Expression::New {
-                    class: Box::new(class),
-                    args: args,
-                }
+                self.parse_list(TokenType::RightBracket)
             },
-            TokenType::Static => {
-                let mut expression = self.parse_expression(0, None)?;
-
-                match expression {
-                    Expression::Closure(ref mut function) => {
-                        if function.has_flags() {
-                            return Err(ParserError::CanOnlyHaveFlag(Flag::Static, "Anonymous functions".to_owned()))
-                        }
-
-                        function.add_flag(Flag::Static);
-                    },
-                    _ => {
-                        return Err(ParserError::UnexpectedExpression(expression))
-                    }
-                }
+            Some(Token { kind: TokenType::List, .. }) => {
+                self.lexer.next();
+                self.expect_left_paren()?;
 
-                expression
+                self.parse_list(TokenType::RightParen)
             },
-            TokenType::ShortFunction => {
-                self.expect_left_paren()?;
+            _ => self.parse_expression(0, None),
+        }
+    }
 
-                let mut parameters: Vec<FunctionParameter> = Vec::new();
+    fn consume_by_ref(&mut self) -> bool {
+        let by_ref = matches!(self.lexer.peek(), Some(Token { kind: TokenType::BitwiseAnd, .. }));
 
-                loop {
-                    let mut next = self.lexer.next();
+        if by_ref {
+            self.lexer.next();
+        }
 
-                    println!("{:?}", next);
+        by_ref
+    }
 
-                    match next {
-                        // break when finding a ), no more parameters
-                        Some(Token {
-                            kind: TokenType::RightParen, ..
-                        }) => break,
-                        // consume trailing commas..
-                        Some(Token { kind: TokenType::Comma, .. }) => {
-                            next = self.lexer.next();
-                        }
-                        Some(Token {
-                            kind: TokenType::Identifier | TokenType::NullableIdentifier | TokenType::Variable,
-                            ..
-                        }) => (),
-                        None => return Err(ParserError::UnexpectedEndOfFile),
-                        _ => {
-                            let t = next.unwrap();
+    fn parse_closure_uses(&mut self, parameters: &[FunctionParameter]) -> Result<Vec<ClosureUse>, ParserError<'p>> {
+        self.expect_left_paren()?;
 
-                            return Err(ParserError::UnexpectedToken(t.kind, t.slice));
-                        }
-                    }
+        let mut uses: Vec<ClosureUse> = Vec::new();
 
-                    let mut name = String::new();
-                    let mut type_hint = None;
+        loop {
+            let mut next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
 
-                    match next {
-                        Some(
-                            t @ Token {
-                                kind: TokenType::Identifier | TokenType::NullableIdentifier, ..
-                            }
-                        ) => type_hint = Some(t.slice.to_string()),
-                        Some(t @ Token { kind: TokenType::Variable, .. }) => {
-                            let mut buffer: String = t.slice.to_string();
-                            buffer.remove(0);
+            let by_ref = next.kind == TokenType::BitwiseAnd;
 
-                            name = buffer;
-                        }
-                        None => return Err(ParserError::UnexpectedEndOfFile),
-                        _ => return Err(ParserError::Unknown),
-                    }
+            if by_ref {
+                next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
+            }
 
-                    if type_hint.is_some() {
-                        let variable = self.expect_token(TokenType::Variable, "")?;
+            if next.kind != TokenType::Variable {
+                return Err(ParserError::UnexpectedToken(next.kind, next.slice));
+            }
 
-                        let mut buffer: String = variable.slice.to_string();
-                        buffer.remove(0);
+            let mut name: String = next.slice.to_string();
+            name.remove(0);
 
-                        name = buffer;
-                    }
+            if uses.iter().any(|closure_use| closure_use.name == name) {
+                return Err(ParserError::DuplicateClosureUse(name));
+            }
 
-                    let next = self.lexer.peek();
-                    let mut default = None;
+            if parameters.iter().any(|parameter| parameter.name == name) {
+                return Err(ParserError::ClosureUseShadowsParameter(name));
+            }
 
-                    if matches!(next, Some(Token { kind: TokenType::Equals, .. })) {
-                        self.lexer.next();
+            uses.push(ClosureUse::new(name, by_ref));
 
-                        default = Some(self.parse_expression(0, None)?);
-                    }
+            let next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
 
-                    parameters.push(FunctionParameter::new(name, type_hint, default))
-                }
+            match next.kind {
+                TokenType::RightParen => break,
+                // a trailing comma, e.g. `use ($a, $b,)`
+                TokenType::Comma if matches!(self.lexer.peek(), Some(Token { kind: TokenType::RightParen, .. })) => {
+                    self.lexer.next();
 
-                let mut return_type_hint = None;
-                let next = self.lexer.next();
+                    break;
+                },
+                TokenType::Comma => {},
+                _ => return Err(ParserError::UnexpectedToken(next.kind, next.slice)),
+            }
+        }
 
-                if matches!(next, Some(Token { kind: TokenType::Colon, .. })) {
-                    let return_type_token = self.expect_token(TokenType::Identifier, "")?;
-
-                    return_type_hint = Some(return_type_token.slice.to_string());
-
-                    self.expect_token(TokenType::DoubleArrow, "{")?;
-                } else if next.is_some()
-                    && !matches!(
-                        next,
-                        Some(Token {
-                            kind: TokenType::DoubleArrow,
-                            ..
-                        })
-                    )
-                {
-                    let next = next.unwrap();
-
-                    return Err(ParserError::ExpectedToken {
-                        expected_type: TokenType::DoubleArrow,
-                        expected_slice: "=>",
-                        got_type: next.kind,
-                        got_slice: next.slice,
-                    });
-                }
+        Ok(uses)
+    }
 
-                let expression = self.parse_expression(0, None)?;
+    fn parse_attribute_groups(&mut self) -> Result<Vec<AttributeGroup>, ParserError<'p>> {
+        let mut groups = Vec::new();
 
-                Expression::Closure(Function::new(
-                    None,
-                    parameters, 
-                    vec![Statement::Expression(expression)],
-                    return_type_hint,
-                    Vec::new(), 
-                    Some(ClosureType::Short)
-                ))
-            },
-            TokenType::Function => {
-                self.expect_left_paren()?;
+        loop {
+            let mut attributes = Vec::new();
 
-                let mut parameters: Vec<FunctionParameter> = Vec::new();
+            loop {
+                let name = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
 
-                loop {
-                    let mut next = self.lexer.next();
+                if name.kind != TokenType::Identifier {
+                    return Err(ParserError::UnexpectedToken(name.kind, name.slice));
+                }
 
-                    println!("{:?}", next);
+                let mut args = Vec::new();
 
-                    match next {
-                        // break when finding a ), no more parameters
-                        Some(Token {
-                            kind: TokenType::RightParen, ..
-                        }) => break,
-                        // consume trailing commas..
-                        Some(Token { kind: TokenType::Comma, .. }) => {
-                            next = self.lexer.next();
-                        }
-                        Some(Token {
-                            kind: TokenType::Identifier | TokenType::NullableIdentifier | TokenType::Variable,
-                            ..
-                        }) => (),
-                        None => return Err(ParserError::UnexpectedEndOfFile),
-                        _ => {
-                            let t = next.unwrap();
+                if matches!(self.lexer.peek(), Some(Token { kind: TokenType::LeftParen, .. })) {
+                    self.lexer.next();
 
-                            return Err(ParserError::UnexpectedToken(t.kind, t.slice));
-                        }
-                    }
+                    args = self.parse_arguments()?;
+                }
 
-                    let mut name = String::new();
-                    let mut type_hint = None;
+                attributes.push(Attribute::new(name.slice.to_owned(), args));
 
-                    match next {
-                        Some(
-                            t @ Token {
-                                kind: TokenType::Identifier | TokenType::NullableIdentifier, ..
-                            }
-                        ) => type_hint = Some(t.slice.to_string()),
-                        Some(t @ Token { kind: TokenType::Variable, .. }) => {
-                            let mut buffer: String = t.slice.to_string();
-                            buffer.remove(0);
+                let next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
 
-                            name = buffer;
-                        }
-                        None => return Err(ParserError::UnexpectedEndOfFile),
-                        _ => return Err(ParserError::Unknown),
-                    }
+                match next.kind {
+                    TokenType::RightBracket => break,
+                    // a trailing comma, e.g. `#[Get, Route('/users'),]`
+                    TokenType::Comma if matches!(self.lexer.peek(), Some(Token { kind: TokenType::RightBracket, .. })) => {
+                        self.lexer.next();
 
-                    if type_hint.is_some() {
-                        let variable = self.expect_token(TokenType::Variable, "")?;
+                        break;
+                    },
+                    TokenType::Comma => {},
+                    _ => return Err(ParserError::UnexpectedToken(next.kind, next.slice)),
+                }
+            }
 
-                        let mut buffer: String = variable.slice.to_string();
-                        buffer.remove(0);
+            groups.push(AttributeGroup::new(attributes));
 
-                        name = buffer;
-                    }
+            // consecutive groups, e.g. `#[Get] #[Route('/users')]`
+            if !matches!(self.lexer.peek(), Some(Token { kind: TokenType::Attribute, .. })) {
+                break;
+            }
 
-                    let next = self.lexer.peek();
-                    let mut default = None;
+            self.lexer.next();
+        }
 
-                    if matches!(next, Some(Token { kind: TokenType::Equals, .. })) {
-                        self.lexer.next();
+        Ok(groups)
+    }
 
-                        default = Some(self.parse_expression(0, None)?);
-                    }
+    fn parse_property(&mut self, token: Token<'p>) -> Result<Property, ParserError<'p>> {
+        let mut next = token;
+        let mut type_hint = None;
 
-                    parameters.push(FunctionParameter::new(name, type_hint, default))
-                }
+        if next.kind != TokenType::Variable {
+            type_hint = Some(self.parse_type(next)?);
+            next = self.expect_token(TokenType::Variable, "")?;
+        }
 
-                let mut return_type_hint = None;
-                let next = self.lexer.next();
+        let mut name: String = next.slice.to_string();
+        name.remove(0);
 
-                if matches!(next, Some(Token { kind: TokenType::Colon, .. })) {
-                    let return_type_token = self.expect_token(TokenType::Identifier, "")?;
+        let mut default = None;
 
-                    return_type_hint = Some(return_type_token.slice.to_string());
+        if let Some(Token { kind: TokenType::Equals, .. }) = self.lexer.peek() {
+            self.lexer.next();
 
-                    self.expect_left_brace()?;
-                } else if next.is_some()
-                    && !matches!(
-                        next,
-                        Some(Token {
-                            kind: TokenType::LeftBrace,
-                            ..
-                        })
-                    )
-                {
-                    let next = next.unwrap();
-
-                    return Err(ParserError::ExpectedToken {
-                        expected_type: TokenType::LeftBrace,
-                        expected_slice: "{",
-                        got_type: next.kind,
-                        got_slice: next.slice,
-                    });
-                }
+            default = Some(self.parse_expression(0, None)?);
+        }
 
-                let mut body = Vec::new();
+        self.expect_token(TokenType::SemiColon, ";")?;
 
-                loop {
-                    let next = self.lexer.next();
+        Ok(Property::new(name, Vec::new(), type_hint, default))
+    }
 
-                    match next {
-                        Some(Token {
-                            kind: TokenType::RightBrace, ..
-                        }) => break,
-                        None => return Err(ParserError::UnexpectedEndOfFile),
-                        _ => {
-                            let statement = self.match_token(next.unwrap())?;
+    fn is_type_start(kind: TokenType) -> bool {
+        matches!(
+            kind,
+            TokenType::Identifier | TokenType::NullableIdentifier | TokenType::Question | TokenType::LeftParen | TokenType::Null | TokenType::False | TokenType::True
+        )
+    }
 
-                            body.push(statement);
-                        }
-                    }
-                }
+    fn parse_type(&mut self, token: Token<'p>) -> Result<Type, ParserError<'p>> {
+        match token.kind {
+            TokenType::NullableIdentifier => {
+                // remove the ?
+                let inner = Type::named(&token.slice[1..]);
 
-                Expression::Closure(Function::new(None, parameters, body, return_type_hint, Vec::new(), Some(ClosureType::Long)))
+                return Ok(Type::Nullable(Box::new(inner)));
             },
-            TokenType::String => {
-                let mut buffer: String = next.slice.to_owned();
-                // remove the quotes
-                buffer.remove(0);
-                buffer.pop();
-
-                Expression::String(buffer)
-            }
-            TokenType::Integer => Expression::Integer(next.slice.parse::<i64>()?),
-            TokenType::Float => Expression::Float(next.slice.parse::<f64>()?),
-            TokenType::Variable => {
-                let mut buffer = next.slice.to_string();
-                // remove the $
-                buffer.remove(0);
-
-                Expression::Variable(buffer)
-            }
-            TokenType::True => Expression::from(true),
-            TokenType::False => Expression::from(false),
-            TokenType::Null => Expression::Null,
-            TokenType::LeftParen => {
-                let expression = self.parse_expression(0, None)?;
-
-                self.expect_right_paren()?;
+            TokenType::Question => {
+                let next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
+                let inner = self.parse_atomic_type(next)?;
 
-                expression
+                return Ok(Type::Nullable(Box::new(inner)));
             },
-            TokenType::LeftBracket => {
-                let mut items = Vec::new();
-                let mut counter = 0;
-
-                loop {
-                    let next = self.lexer.peek();
-
-                    match next {
-                        Some(Token {
-                            kind: TokenType::RightBracket, ..
-                        }) => {
-                            self.lexer.next();
+            _ => (),
+        }
 
-                            break;
-                        }
-                        Some(Token { kind: TokenType::Comma, .. }) => {
-                            self.lexer.next();
+        let mut types = vec![self.parse_intersection_type(token)?];
 
-                            continue;
-                        }
-                        None => return Err(ParserError::UnexpectedEndOfFile),
-                        _ => {
-                            let expression = self.parse_expression(0, None)?;
-
-                            match expression {
-                                Expression::ArrayItem { ref key, .. } => {
-                                    match **key {
-                                        Expression::Integer(i) => counter = i + 1,
-                                        Expression::Float(f) => counter = (f as i64) + 1,
-                                        _ => (),
-                                    }
-
-                                    items.push(expression)
-                                }
-                                _ => {
-                                    let key = Expression::Integer(counter.clone());
+        while let Some(Token { kind: TokenType::BitwiseOr, .. }) = self.lexer.peek() {
+            self.lexer.next();
 
-                                    items.push(Expression::ArrayItem {
-                                        key: Box::new(key),
-                                        value: Box::new(expression),
-                                    });
+            let next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
 
-                                    counter += 1
-                                }
-                            }
-                        }
-                    }
-                }
+            types.push(self.parse_intersection_type(next)?);
+        }
 
-                Expression::Array(items)
-            }
-            TokenType::Identifier | TokenType::NullableIdentifier => {
-                match self.lexer.clone().next() {
-                    Some(Token {
-                        kind: TokenType::Variable,
-                        slice,
-                        ..
-                    }) => {
-                        let mut buffer = slice.to_string();
-                        // remove the $
-                        buffer.remove(0);
+        Ok(if types.len() == 1 { types.remove(0) } else { Type::Union(types) })
+    }
 
-                        self.lexer.next();
+    fn parse_intersection_type(&mut self, token: Token<'p>) -> Result<Type, ParserError<'p>> {
+        // disjunctive normal form, e.g. `(A&B)|null`
+        if token.kind == TokenType::LeftParen {
+            let next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
+            let intersection = self.parse_intersection_type(next)?;
 
-                        Expression::TypedVariable(next.slice.to_owned(), buffer)
-                    }
-                    _ => Expression::Identifier(next.slice.to_owned()),
-                }
-            },
-            TokenType::Minus => {
-                let maybe_bp = BindingPower::prefix(TokenType::Minus);
+            self.expect_right_paren()?;
 
-                if maybe_bp.is_none() {
-                    return Err(ParserError::Unknown);
-                }
+            return Ok(intersection);
+        }
 
-                let ((), rbp) = maybe_bp.unwrap();
+        let mut types = vec![self.parse_atomic_type(token)?];
 
-                let rhs = self.parse_expression(rbp, None)?;
+        loop {
+            let mut lexer = self.lexer.lookahead();
 
-                Expression::Unary(Box::new(rhs))
-            },
-            TokenType::Not => {
-                let maybe_bp = BindingPower::prefix(TokenType::Not);
+            // `Foo &$foo` is a by-reference parameter, not an intersection
+            match (lexer.next(), lexer.next()) {
+                (Some(Token { kind: TokenType::BitwiseAnd, .. }), Some(Token { kind: TokenType::Variable | TokenType::Ellipsis, .. })) => break,
+                (Some(Token { kind: TokenType::BitwiseAnd, .. }), _) => (),
+                _ => break,
+            }
 
-                if maybe_bp.is_none() {
-                    return Err(ParserError::Unknown);
-                }
+            self.lexer.next();
 
-                let ((), rbp) = maybe_bp.unwrap();
+            let next = self.lexer.next().ok_or(ParserError::UnexpectedEndOfFile)?;
 
-                let rhs = self.parse_expression(rbp, None)?;
+            types.push(self.parse_atomic_type(next)?);
+        }
 
-                Expression::Negate(Box::new(rhs))
-            },
-            TokenType::BitwiseNot => {
-                let maybe_bp = BindingPower::prefix(TokenType::BitwiseNot);
+        Ok(if types.len() == 1 { types.remove(0) } else { Type::Intersection(types) })
+    }
 
-                if maybe_bp.is_none() {
-                    return Err(ParserError::Unknown);
-                }
+    fn parse_atomic_type(&mut self, token: Token<'p>) -> Result<Type, ParserError<'p>> {
+        match token.kind {
+            TokenType::Identifier | TokenType::Static | TokenType::Null | TokenType::False | TokenType::True => Ok(Type::named(token.slice)),
+            _ => Err(ParserError::UnexpectedToken(token.kind, token.slice)),
+        }
+    }
 
-                let ((), rbp) = maybe_bp.unwrap();
+    fn parse_member_name(&mut self) -> Result<Expression, ParserError<'p>> {
+        let next = self.lexer.next();
 
-                let rhs = self.parse_expression(rbp, None)?;
+        match next {
+            Some(t @ Token { kind: TokenType::Variable, .. }) => {
+                let mut buffer = t.slice.to_string();
+                // remove the $
+                buffer.remove(0);
 
-                Expression::BitwiseNot(Box::new(rhs))
+                Ok(Expression::Variable(buffer))
             },
-            _ => {
-                unimplemented!()
-            }
-        };
+            Some(Token { kind: TokenType::LeftBrace, .. }) => {
+                let expression = self.parse_expression(0, None)?;
 
-        loop {
-            let next = self.lexer.peek();
+                self.expect_right_brace()?;
 
-            if next.is_none() {
-                return Err(ParserError::UnexpectedEndOfFile);
-            }
+                Ok(expression)
+            },
+            // keywords are valid member names too, e.g. `$query->list`
+            Some(t) if Self::is_identifier_like(t.slice) => Ok(Expression::Identifier(t.slice.to_owned())),
+            None => Err(ParserError::UnexpectedEndOfFile),
+            Some(t) => Err(ParserError::UnexpectedToken(t.kind, t.slice)),
+        }
+    }
 
-            let op = next.unwrap();
+    /// Like `parse_arguments`, but also accepts the first-class callable syntax `(...)`.
+    fn parse_argument_list(&mut self) -> Result<ArgumentList, ParserError<'p>> {
+        let mut lexer = self.lexer.lookahead();
 
-            if let Some((lbp, _)) = BindingPower::postfix(op.kind) {
-                if lbp < bp {
-                    break;
-                }
+        if matches!((lexer.next(), lexer.next()), (Some(Token { kind: TokenType::Ellipsis, .. }), Some(Token { kind: TokenType::RightParen, .. }))) {
+            self.lexer.next();
+            self.lexer.next();
 
-                let op = self.lexer.next().unwrap();
+            return Ok(ArgumentList::FirstClassCallable);
+        }
 
-                lhs = match op.kind {
-                    TokenType::Arrow => {
-                        let next = self.lexer.next();
+        Ok(ArgumentList::Arguments(self.parse_arguments()?))
+    }
 
-                        match next {
-                            Some(t @ Token { kind: TokenType::Identifier, .. }) => {
-                                Expression::PropertyAccess(Box::new(lhs), Box::new(Expression::Identifier(t.slice.to_owned())))
-                            },
-                            None => return Err(ParserError::UnexpectedEndOfFile),
-                            _ => {
-                                let t = next.unwrap();
-                                
-                                return Err(ParserError::UnexpectedToken(t.kind, t.slice))
-                            }
-                        }
-                    },
-                    TokenType::LeftBracket => {
-                        let next = self.lexer.next();
+    fn parse_arguments(&mut self) -> Result<Vec<Argument>, ParserError<'p>> {
+        let mut args: Vec<Argument> = Vec::new();
 
-                        let expression = match next {
-                            Some(Token {
-                                kind: TokenType::RightBracket, ..
-                            }) => None,
-                            None => return Err(ParserError::UnexpectedEndOfFile),
-                            _ => {
-                                let index = self.parse_expression(0, next)?;
+        loop {
+            let next = self.lexer.next();
 
-                                self.expect_token(TokenType::RightBracket, "]")?;
+            let token = match next {
+                Some(t) => t,
+                None => return Err(ParserError::UnexpectedEndOfFile),
+            };
 
-                                Some(Box::new(index))
-                            }
-                        };
+            let mut name = None;
 
-                        Expression::ArrayAccess(Box::new(lhs.clone()), expression)
+            let value = match token.kind {
+                TokenType::RightParen => break,
+                TokenType::Comma => {
+                    if args.is_empty() {
+                        return Err(ParserError::UnexpectedToken(TokenType::Comma, ","))
                     }
-                    TokenType::LeftParen => {
-                        let mut args = Vec::new();
-
-                        loop {
-                            let next = self.lexer.next();
-
-                            let token = match next {
-                                Some(t) => t,
-                                None => return Err(ParserError::UnexpectedEndOfFile),
-                            };
-
-                            match token.kind {
-                                TokenType::RightParen => break,
-                                TokenType::Comma => {
-                                    if args.is_empty() {
-                                        return Err(ParserError::UnexpectedToken(TokenType::Comma, ","))
-                                    }
-
-                                    continue
-                                },
-                                _ => {
-                                    let expression = self.parse_expression(0, next)?;
-
-                                    args.push(expression)
-                                }
-                            }
-                        }
 
-                        Expression::Call {
-                            target: Box::new(lhs),
-                            args: args,
-                        }
-                    }
-                    _ => unreachable!(),
-                };
+                    continue
+                },
+                TokenType::Ellipsis => {
+                    let expression = self.parse_expression(0, None)?;
 
-                continue;
-            } else if let Some((lbp, rbp)) = BindingPower::infix(op.kind) {
-                if lbp < bp {
-                    break;
-                }
+                    Expression::Spread(Box::new(expression))
+                },
+                TokenType::BitwiseAnd => {
+                    let expression = self.parse_expression(0, None)?;
 
-                let op = self.lexer.next().unwrap();
+                    Expression::Reference(Box::new(expression))
+                },
+                // named arguments, e.g. `limit: 10`
+                _ if Self::is_identifier_like(token.slice) && matches!(self.lexer.peek(), Some(Token { kind: TokenType::Colon, .. })) => {
+                    self.lexer.next();
 
-                let rhs = self.parse_expression(rbp, None)?;
+                    if args.iter().any(|arg| arg.name.as_deref() == Some(token.slice)) {
+                        return Err(ParserError::DuplicateNamedArgument(token.slice.to_owned()));
+                    }
 
-                lhs = Expression::make_infix(lhs, &op.kind, rhs);
+                    name = Some(token.slice.to_owned());
 
-                continue;
+                    self.parse_expression(0, None)?
+                },
+                _ => self.parse_expression(0, next)?,
+            };
+
+            if name.is_none() && args.iter().any(Argument::is_named) {
+                return Err(ParserError::PositionalArgumentAfterNamedArgument);
             }
 
-            break;
+            args.push(Argument::new(name, value))
         }
 
-        Ok(lhs)
+        Ok(args)
+    }
+
+    fn is_identifier_like(slice: &str) -> bool {
+        slice.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
     }
 
     fn expect_left_paren(&mut self) -> Result<Token, ParserError<'p>> {
@@ -1257,15 +2170,23 @@ impl<'p> Parser<'p> {
     }
 
     #[allow(clippy::while_let_on_iterator)]
-    pub fn all(&'p mut self) -> Result<Program, ParserError> {
+    pub fn all(&mut self) -> Result<Program, ParserError<'p>> {
         let mut program = Vec::new();
 
         while let Some(token) = self.lexer.next() {
-            let statement = self.match_token(token)?;
+            let statement = self.match_statement(token)?;
 
             program.push(statement);
         }
 
+        self.lexer.release_comments(0..usize::MAX);
+
         Ok(program)
     }
+
+    /// The comments that are not part of a declaration's trivia, such as those
+    /// around expression statements or inside expressions, in source order.
+    pub fn comments(&self) -> impl Iterator<Item = &Comment> {
+        self.lexer.unclaimed_comments()
+    }
 }
@@ -0,0 +1,464 @@
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct DocBlock {
+    pub summary: String,
+    pub description: String,
+    pub tags: Vec<Tag>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub enum Tag {
+    Param {
+        type_hint: Option<DocType>,
+        name: String,
+        description: String,
+    },
+    Return {
+        type_hint: DocType,
+        description: String,
+    },
+    Var {
+        type_hint: DocType,
+        name: Option<String>,
+        description: String,
+    },
+    Throws {
+        type_hint: DocType,
+        description: String,
+    },
+    Template {
+        name: String,
+        bound: Option<DocType>,
+        description: String,
+    },
+    Deprecated {
+        description: String,
+    },
+    Property {
+        type_hint: DocType,
+        name: String,
+        description: String,
+    },
+    /// Any tag that isn't understood, or a known tag whose body couldn't be parsed.
+    Other {
+        name: String,
+        body: String,
+    },
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub enum DocType {
+    Named(String),
+    Literal(String),
+    Nullable(Box<DocType>),
+    Union(Vec<DocType>),
+    Intersection(Vec<DocType>),
+    Array(Box<DocType>),
+    Generic {
+        name: String,
+        args: Vec<DocType>,
+    },
+    Shape {
+        name: String,
+        fields: Vec<ShapeField>,
+    },
+    Callable {
+        name: String,
+        parameters: Vec<DocType>,
+        return_type: Option<Box<DocType>>,
+    },
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ShapeField {
+    pub key: Option<String>,
+    pub optional: bool,
+    pub value: DocType,
+}
+
+impl DocBlock {
+    /// Parses a doc comment, with or without its `/**` and `*/` delimiters.
+    /// The summary runs up to the first blank line or the first line ending
+    /// in a full stop, and the description runs from there to the first tag.
+    pub fn parse(comment: &str) -> Self {
+        let comment = comment.trim();
+        let comment = comment.strip_prefix("/**").unwrap_or(comment);
+        let comment = comment.strip_suffix("*/").unwrap_or(comment);
+
+        let lines: Vec<&str> = comment
+            .lines()
+            .map(|line| {
+                let line = line.trim();
+
+                line.strip_prefix('*').unwrap_or(line).trim()
+            })
+            .collect();
+
+        let first_tag = lines.iter().position(|line| line.starts_with('@')).unwrap_or(lines.len());
+        let (text, tag_lines) = lines.split_at(first_tag);
+
+        let mut text = text.iter().skip_while(|line| line.is_empty());
+        let mut summary = Vec::new();
+
+        for line in text.by_ref() {
+            if line.is_empty() {
+                break;
+            }
+
+            summary.push(*line);
+
+            if line.ends_with('.') {
+                break;
+            }
+        }
+
+        let description: Vec<&str> = text.copied().collect();
+
+        let mut tags: Vec<(&str, String)> = Vec::new();
+
+        for line in tag_lines {
+            match line.strip_prefix('@') {
+                Some(tag) => {
+                    let (name, body) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+
+                    tags.push((name, body.trim().to_owned()));
+                },
+                None => if let Some((_, body)) = tags.last_mut() {
+                    body.push('\n');
+                    body.push_str(line);
+                },
+            }
+        }
+
+        Self {
+            summary: summary.join(" "),
+            description: description.join("\n").trim().to_owned(),
+            tags: tags.into_iter().map(|(name, body)| Tag::parse(name, body.trim())).collect(),
+        }
+    }
+}
+
+impl Tag {
+    pub fn parse(name: &str, body: &str) -> Self {
+        Self::parse_known(name, body).unwrap_or_else(|| Self::Other {
+            name: name.to_owned(),
+            body: body.to_owned(),
+        })
+    }
+
+    fn parse_known(name: &str, body: &str) -> Option<Self> {
+        let mut parser = TypeParser::new(body);
+
+        Some(match name {
+            "param" => {
+                let type_hint = if parser.at_variable() { None } else { Some(parser.parse()?) };
+                let name = parser.parse_variable()?;
+
+                Self::Param { type_hint, name, description: parser.rest() }
+            },
+            "return" => Self::Return { type_hint: parser.parse()?, description: parser.rest() },
+            "var" => {
+                let type_hint = parser.parse()?;
+                let name = if parser.at_variable() { Some(parser.parse_variable()?) } else { None };
+
+                Self::Var { type_hint, name, description: parser.rest() }
+            },
+            "throws" => Self::Throws { type_hint: parser.parse()?, description: parser.rest() },
+            "template" => {
+                let name = parser.parse_identifier()?;
+                let bound = if parser.eat_word("of") || parser.eat_word("as") { Some(parser.parse()?) } else { None };
+
+                Self::Template { name, bound, description: parser.rest() }
+            },
+            "deprecated" => Self::Deprecated { description: body.to_owned() },
+            "property" => {
+                let type_hint = parser.parse()?;
+                let name = parser.parse_variable()?;
+
+                Self::Property { type_hint, name, description: parser.rest() }
+            },
+            _ => return None,
+        })
+    }
+}
+
+impl DocType {
+    pub fn parse(source: &str) -> Option<Self> {
+        let mut parser = TypeParser::new(source);
+        let doc_type = parser.parse()?;
+
+        if parser.rest().is_empty() { Some(doc_type) } else { None }
+    }
+}
+
+/// A small recursive descent parser for the PHPDoc type syntax. Whitespace is
+/// only skipped where the type must continue, so that a type can be followed
+/// by a variable name and free-form description text.
+struct TypeParser<'a> {
+    source: &'a str,
+    position: usize,
+}
+
+impl<'a> TypeParser<'a> {
+    fn new(source: &'a str) -> Self {
+        Self { source, position: 0 }
+    }
+
+    fn remaining(&self) -> &'a str {
+        &self.source[self.position..]
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.remaining().bytes().next()
+    }
+
+    fn peek_after_whitespace(&self) -> Option<u8> {
+        self.remaining().trim_start().bytes().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.position = self.source.len() - self.remaining().trim_start().len();
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        if self.peek_after_whitespace() == Some(byte) {
+            self.skip_whitespace();
+            self.position += 1;
+
+            return true;
+        }
+
+        false
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let remaining = self.remaining().trim_start();
+
+        match remaining.strip_prefix(word) {
+            Some(rest) if rest.starts_with(char::is_whitespace) => {
+                self.position = self.source.len() - rest.len();
+
+                true
+            },
+            _ => false,
+        }
+    }
+
+    fn rest(&self) -> String {
+        self.remaining().trim().to_owned()
+    }
+
+    fn at_variable(&self) -> bool {
+        matches!(self.peek_after_whitespace(), Some(b'$' | b'&' | b'.'))
+    }
+
+    fn parse_variable(&mut self) -> Option<String> {
+        self.eat(b'&');
+        self.skip_whitespace();
+
+        if self.remaining().starts_with("...") {
+            self.position += 3;
+        }
+
+        if !self.eat(b'$') {
+            return None;
+        }
+
+        let length = self.remaining().bytes().take_while(|byte| Self::is_name_byte(*byte) && *byte != b'\\' && *byte != b'-').count();
+
+        if length == 0 {
+            return None;
+        }
+
+        let name = &self.remaining()[..length];
+        self.position += length;
+
+        Some(name.to_owned())
+    }
+
+    fn is_name_byte(byte: u8) -> bool {
+        byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'\\' | b'-') || byte >= 0x80
+    }
+
+    fn parse_identifier(&mut self) -> Option<String> {
+        self.skip_whitespace();
+
+        if self.remaining().starts_with("$this") {
+            self.position += 5;
+
+            return Some("$this".to_owned());
+        }
+
+        match self.peek() {
+            Some(byte) if Self::is_name_byte(byte) && byte != b'-' && !byte.is_ascii_digit() => (),
+            _ => return None,
+        }
+
+        let length = self.remaining().bytes().take_while(|byte| Self::is_name_byte(*byte)).count();
+        let name = &self.remaining()[..length];
+        self.position += length;
+
+        Some(name.to_owned())
+    }
+
+    fn parse(&mut self) -> Option<DocType> {
+        self.skip_whitespace();
+
+        let mut types = vec![self.parse_intersection()?];
+
+        while self.eat(b'|') {
+            types.push(self.parse_intersection()?);
+        }
+
+        Some(if types.len() == 1 { types.remove(0) } else { DocType::Union(types) })
+    }
+
+    fn parse_intersection(&mut self) -> Option<DocType> {
+        let mut types = vec![self.parse_postfix()?];
+
+        // `int &$foo` is a by-reference parameter, not an intersection.
+        while self.peek_after_whitespace() == Some(b'&') && !matches!(self.remaining().trim_start()[1..].trim_start().bytes().next(), Some(b'$' | b'.')) {
+            self.eat(b'&');
+            types.push(self.parse_postfix()?);
+        }
+
+        Some(if types.len() == 1 { types.remove(0) } else { DocType::Intersection(types) })
+    }
+
+    fn parse_postfix(&mut self) -> Option<DocType> {
+        let mut doc_type = self.parse_atomic()?;
+
+        while self.remaining().starts_with("[]") {
+            self.position += 2;
+            doc_type = DocType::Array(Box::new(doc_type));
+        }
+
+        Some(doc_type)
+    }
+
+    fn parse_atomic(&mut self) -> Option<DocType> {
+        self.skip_whitespace();
+
+        match self.peek()? {
+            b'?' => {
+                self.position += 1;
+
+                Some(DocType::Nullable(Box::new(self.parse_postfix()?)))
+            },
+            b'(' => {
+                self.position += 1;
+                let doc_type = self.parse()?;
+
+                if self.eat(b')') { Some(doc_type) } else { None }
+            },
+            quote @ (b'\'' | b'"') => {
+                let end = self.remaining()[1..].find(quote as char)? + 2;
+                let literal = &self.remaining()[..end];
+                self.position += end;
+
+                Some(DocType::Literal(literal.to_owned()))
+            },
+            byte if byte.is_ascii_digit() || byte == b'-' => {
+                let length = 1 + self.remaining()[1..].bytes().take_while(|byte| byte.is_ascii_digit() || *byte == b'.' || *byte == b'_').count();
+                let literal = &self.remaining()[..length];
+                self.position += length;
+
+                Some(DocType::Literal(literal.to_owned()))
+            },
+            _ => {
+                let name = self.parse_identifier()?;
+
+                match self.peek() {
+                    Some(b'<') => {
+                        self.position += 1;
+                        let args = self.parse_list(b'>', Self::parse)?;
+
+                        Some(DocType::Generic { name, args })
+                    },
+                    Some(b'{') if matches!(name.to_ascii_lowercase().as_str(), "array" | "list" | "object") => {
+                        self.position += 1;
+                        let fields = self.parse_list(b'}', Self::parse_shape_field)?;
+
+                        Some(DocType::Shape { name, fields })
+                    },
+                    Some(b'(') if matches!(name.to_ascii_lowercase().trim_start_matches('\\'), "callable" | "closure" | "pure-callable" | "pure-closure") => {
+                        self.position += 1;
+                        let parameters = self.parse_list(b')', Self::parse_callable_parameter)?;
+                        let return_type = if self.eat(b':') { Some(Box::new(self.parse_postfix()?)) } else { None };
+
+                        Some(DocType::Callable { name, parameters, return_type })
+                    },
+                    _ => Some(DocType::Named(name)),
+                }
+            },
+        }
+    }
+
+    /// Parses comma separated items up to and including the closing byte,
+    /// allowing a trailing comma.
+    fn parse_list<T>(&mut self, close: u8, mut item: impl FnMut(&mut Self) -> Option<T>) -> Option<Vec<T>> {
+        let mut items = Vec::new();
+
+        while !self.eat(close) {
+            items.push(item(self)?);
+
+            if !self.eat(b',') {
+                return if self.eat(close) { Some(items) } else { None };
+            }
+        }
+
+        Some(items)
+    }
+
+    fn parse_shape_field(&mut self) -> Option<ShapeField> {
+        let start = self.position;
+        self.skip_whitespace();
+
+        let key = match self.peek()? {
+            b'\'' | b'"' => match self.parse_atomic()? {
+                DocType::Literal(literal) => Some(literal[1..literal.len() - 1].to_owned()),
+                _ => None,
+            },
+            byte if byte.is_ascii_digit() => {
+                let length = self.remaining().bytes().take_while(u8::is_ascii_digit).count();
+                let key = &self.remaining()[..length];
+                self.position += length;
+
+                Some(key.to_owned())
+            },
+            _ => self.parse_identifier(),
+        };
+
+        let optional = key.is_some() && self.remaining().starts_with('?');
+
+        if optional {
+            self.position += 1;
+        }
+
+        if key.is_some() && self.eat(b':') {
+            return Some(ShapeField { key, optional, value: self.parse()? });
+        }
+
+        self.position = start;
+
+        Some(ShapeField { key: None, optional: false, value: self.parse()? })
+    }
+
+    fn parse_callable_parameter(&mut self) -> Option<DocType> {
+        let doc_type = self.parse()?;
+
+        if self.at_variable() {
+            self.parse_variable();
+        } else if self.remaining().trim_start().starts_with("...") {
+            self.skip_whitespace();
+            self.position += 3;
+        }
+
+        if self.eat(b'=') {
+            self.skip_whitespace();
+        }
+
+        Some(doc_type)
+    }
+}
@@ -1,34 +1,43 @@
+use crate::AttributeGroup;
 use crate::Expression;
 use crate::{Flag, Flaggable};
 use crate::Nullable;
+use crate::Type;
+use crate::Trivia;
 use serde::Serialize;
 
 #[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct Property {
     pub name: String,
     flags: Vec<Flag>,
-    type_hint: Option<String>,
+    type_hint: Option<Type>,
     default: Option<Expression>,
+    pub attributes: Vec<AttributeGroup>,
+    pub doc_comment: Option<String>,
+    pub trivia: Trivia,
 }
 
 impl Property {
-    pub fn new(name: String, flags: Vec<Flag>, type_hint: Option<String>, default: Option<Expression>) -> Self {
+    pub fn new(name: String, flags: Vec<Flag>, type_hint: Option<Type>, default: Option<Expression>) -> Self {
         Self {
             name,
             flags,
             type_hint,
             default,
+            attributes: Vec::new(),
+            doc_comment: None,
+            trivia: Trivia::default(),
         }
     }
+
+    pub fn is_typed(&self) -> bool {
+        self.type_hint.is_some()
+    }
 }
 
 impl Nullable for Property {
     fn is_nullable(&self) -> bool {
-        if self.type_hint.is_none() {
-            return false;
-        }
-
-        self.type_hint.clone().unwrap().starts_with("?")
+        self.type_hint.as_ref().is_some_and(Nullable::is_nullable)
     }
 }
 
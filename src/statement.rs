@@ -1,24 +1,31 @@
 use crate::Class;
+use crate::ClassConst;
+use crate::Constant;
 use crate::Else;
 use crate::Expression;
 use crate::Function;
 use crate::If;
+use crate::Interface;
 use crate::Property;
+use crate::StaticVar;
+use crate::Trivia;
 
 use serde::Serialize;
 
 #[derive(Serialize, Debug, Clone, PartialEq)]
 pub enum Statement {
     OpenTag,
-    Break,
+    Break(Option<Expression>),
     Continue(Option<Expression>),
-    DocBlock(String),
     Echo(Expression),
-    Return(Expression),
+    Return(Option<Expression>),
     Expression(Expression),
     Function(Function),
     Class(Class),
+    Interface(Interface),
     Property(Property),
+    ClassConst(ClassConst),
+    Const(Vec<Constant>),
     If(If),
     ElseIf(If),
     Else(Else),
@@ -34,8 +41,63 @@ pub enum Statement {
         expression: Expression,
         key_var: Option<Expression>,
         value_var: Expression,
+        by_ref: bool,
         body: Vec<Statement>,
     },
+    Namespace(Expression),
     Use(Expression),
     UseTrait(Expression),
+    Unset(Vec<Expression>),
+    Global(Vec<String>),
+    StaticVar(Vec<StaticVar>),
+    Declare {
+        directives: Vec<Constant>,
+        body: Vec<Statement>,
+    },
+    Goto(String),
+    Label(String),
+    /// `__halt_compiler();` and the raw data that follows it, if the parser
+    /// was given the source.
+    HaltCompiler(Option<String>),
+}
+
+impl Statement {
+    /// The expressions that belong directly to this statement, excluding those
+    /// in nested blocks and in nested function or class declarations.
+    pub fn expressions(&self) -> Vec<&Expression> {
+        match self {
+            Self::Break(Some(expression)) | Self::Continue(Some(expression)) | Self::Return(Some(expression)) => vec![expression],
+            Self::Echo(expression) | Self::Expression(expression) => vec![expression],
+            Self::If(r#if) | Self::ElseIf(r#if) => r#if.conditions(),
+            Self::While { condition, .. } | Self::DoWhile { condition, .. } => vec![condition],
+            Self::Foreach { expression, key_var, value_var, .. } => std::iter::once(expression).chain(key_var).chain(std::iter::once(value_var)).collect(),
+            Self::Const(constants) | Self::Declare { directives: constants, .. } => constants.iter().map(|constant| &constant.value).collect(),
+            Self::Unset(expressions) => expressions.iter().collect(),
+            Self::StaticVar(vars) => vars.iter().filter_map(|var| var.default.as_ref()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The doc comment and trivia of the declaration held by this statement,
+    /// for the statements that can carry comments.
+    pub(crate) fn comments_mut(&mut self) -> Option<(&mut Option<String>, &mut Trivia)> {
+        match self {
+            Self::Function(function) => Some((&mut function.doc_comment, &mut function.trivia)),
+            Self::Class(class) => Some((&mut class.doc_comment, &mut class.trivia)),
+            Self::Interface(interface) => Some((&mut interface.doc_comment, &mut interface.trivia)),
+            Self::Property(property) => Some((&mut property.doc_comment, &mut property.trivia)),
+            Self::ClassConst(constant) => Some((&mut constant.doc_comment, &mut constant.trivia)),
+            Self::Const(constants) => constants.first_mut().map(|constant| (&mut constant.doc_comment, &mut constant.trivia)),
+            _ => None,
+        }
+    }
+
+    pub fn blocks(&self) -> Vec<&[Statement]> {
+        match self {
+            Self::If(r#if) | Self::ElseIf(r#if) => r#if.blocks(),
+            Self::Else(r#else) => vec![r#else.then()],
+            Self::While { body, .. } | Self::DoWhile { body, .. } | Self::Foreach { body, .. } | Self::Declare { body, .. } => vec![body],
+            _ => Vec::new(),
+        }
+    }
 }
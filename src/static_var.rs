@@ -0,0 +1,16 @@
+use crate::Expression;
+
+use serde::Serialize;
+
+/// A function-local static variable, e.g. `static $count = 0;`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct StaticVar {
+    pub name: String,
+    pub default: Option<Expression>,
+}
+
+impl StaticVar {
+    pub fn new(name: String, default: Option<Expression>) -> Self {
+        Self { name, default }
+    }
+}
@@ -0,0 +1,199 @@
+use crate::Comment;
+
+use std::iter::Peekable;
+use std::ops::Range;
+use tusk_lexer::{Lexer, Token, TokenType};
+
+/// The lexer's tokens, with attributes split out of `#` comments. The lexer
+/// reads `#[Route('/')] public function index() {}` as a single comment that
+/// runs to the end of the line, so everything after the `#[` is lexed again.
+#[derive(Debug, Clone)]
+struct Tokens<'a> {
+    lexer: Lexer<'a>,
+    line: Option<Lexer<'a>>,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = match self.line.as_mut().and_then(Iterator::next) {
+            Some(token) => token,
+            None => {
+                self.line = None;
+                self.lexer.next()?
+            },
+        };
+
+        match token.slice.strip_prefix("#[") {
+            Some(rest) if token.kind == TokenType::Comment => {
+                self.line = Some(Lexer::new(rest));
+
+                Some(Token { kind: TokenType::Attribute, slice: &token.slice[..2] })
+            },
+            _ => Some(token),
+        }
+    }
+}
+
+/// A comment waiting to be claimed, along with the index of the token that
+/// follows it.
+#[derive(Debug, Clone)]
+struct Buffered {
+    comment: Comment,
+    before: usize,
+    same_line: bool,
+}
+
+/// Wraps the lexer so that the parser never sees comment tokens. Comments are
+/// buffered instead, until the parser takes them to attach to a node or sets
+/// them aside as unclaimed.
+///
+/// This is deliberately not `Clone`, as copying the buffered comments on every
+/// lookahead makes parsing quadratic in the number of comments. Use `lookahead`
+/// or `peek_nth` instead.
+#[derive(Debug)]
+pub(crate) struct TokenStream<'a> {
+    lexer: Peekable<Tokens<'a>>,
+    source: Option<&'a str>,
+    comments: Vec<Buffered>,
+    unclaimed: Vec<Buffered>,
+    position: usize,
+    // where the last token, or a comment on the same line as it, ends in `source`
+    line_end: Option<usize>,
+}
+
+impl<'a> TokenStream<'a> {
+    /// Without `source`, there is no telling which line a comment is on, so
+    /// none of them count as trailing the token before them.
+    pub fn new(lexer: Lexer<'a>, source: Option<&'a str>) -> Self {
+        Self {
+            lexer: Tokens { lexer, line: None }.peekable(),
+            source,
+            comments: Vec::new(),
+            unclaimed: Vec::new(),
+            position: 0,
+            line_end: None,
+        }
+    }
+
+    pub fn peek(&mut self) -> Option<&Token<'a>> {
+        while matches!(self.lexer.peek(), Some(Token { kind: TokenType::Comment | TokenType::DocBlockComment, .. })) {
+            let token = self.lexer.next().unwrap();
+
+            self.buffer(token);
+        }
+
+        self.lexer.peek()
+    }
+
+    /// The tokens after those consumed so far, without consuming them. Only the
+    /// lexer is copied, and comments are skipped rather than buffered.
+    pub fn lookahead(&self) -> impl Iterator<Item = Token<'a>> {
+        self.lexer.clone().filter(|token| !Self::is_comment(token))
+    }
+
+    pub fn peek_nth(&self, n: usize) -> Option<Token<'a>> {
+        self.lookahead().nth(n)
+    }
+
+    /// The index of the next token, which is also how many have been consumed.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Takes the comments directly before the token at `index`.
+    pub fn take_comments(&mut self, index: usize) -> Vec<Comment> {
+        self.take(index..index + 1).map(|buffered| buffered.comment).collect()
+    }
+
+    /// Takes the comments directly before the token at `index` that are on the
+    /// same line as the token before them. A doc block ends the run, as it
+    /// documents whatever follows it.
+    pub fn take_trailing_comments(&mut self, index: usize) -> Vec<Comment> {
+        let start = self.comments.partition_point(|comment| comment.before < index);
+        let count = self.comments[start..].iter().take_while(|comment| comment.before == index && comment.same_line && !comment.comment.is_doc_block()).count();
+
+        self.comments.drain(start..start + count).map(|buffered| buffered.comment).collect()
+    }
+
+    /// Sets the comments before the tokens in `tokens` aside as unclaimed.
+    pub fn release_comments(&mut self, tokens: Range<usize>) {
+        let released: Vec<Buffered> = self.take(tokens).collect();
+
+        for comment in released {
+            let at = self.unclaimed.partition_point(|unclaimed| unclaimed.before <= comment.before);
+
+            self.unclaimed.insert(at, comment);
+        }
+    }
+
+    /// Sets `comments`, which came directly before the token at `index`, aside
+    /// as unclaimed.
+    pub fn release(&mut self, index: usize, comments: Vec<Comment>) {
+        let at = self.unclaimed.partition_point(|unclaimed| unclaimed.before <= index);
+
+        self.unclaimed.splice(at..at, comments.into_iter().map(|comment| Buffered { comment, before: index, same_line: false }));
+    }
+
+    /// The comments that no node claimed, in source order.
+    pub fn unclaimed_comments(&self) -> impl Iterator<Item = &Comment> {
+        self.unclaimed.iter().map(|buffered| &buffered.comment)
+    }
+
+    // the buffer is in source order, so the comments before a run of tokens sit together
+    fn take(&mut self, tokens: Range<usize>) -> impl Iterator<Item = Buffered> + '_ {
+        let start = self.comments.partition_point(|comment| comment.before < tokens.start);
+        let end = self.comments.partition_point(|comment| comment.before < tokens.end);
+
+        self.comments.drain(start..end)
+    }
+
+    fn is_comment(token: &Token<'a>) -> bool {
+        matches!(token.kind, TokenType::Comment | TokenType::DocBlockComment)
+    }
+
+    fn offset(&self, slice: &str) -> Option<usize> {
+        let source = self.source?;
+        let start = (slice.as_ptr() as usize).checked_sub(source.as_ptr() as usize)?;
+
+        (start + slice.len() <= source.len()).then_some(start)
+    }
+
+    fn buffer(&mut self, token: Token<'a>) {
+        let comment = match token.kind {
+            TokenType::DocBlockComment => Comment::DocBlock(token.slice.to_owned()),
+            _ if token.slice.starts_with("/*") => Comment::Block(token.slice.to_owned()),
+            _ => Comment::Line(token.slice.to_owned()),
+        };
+
+        let start = self.offset(token.slice);
+        let same_line = match (self.source, self.line_end, start) {
+            (Some(source), Some(end), Some(start)) => source.get(end..start).is_some_and(|gap| !gap.contains('\n')),
+            _ => false,
+        };
+
+        self.line_end = if same_line { start.map(|start| start + token.slice.len()) } else { None };
+
+        self.comments.push(Buffered { comment, before: self.position, same_line });
+    }
+}
+
+impl<'a> Iterator for TokenStream<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        loop {
+            match self.lexer.next() {
+                Some(token @ Token { kind: TokenType::Comment | TokenType::DocBlockComment, .. }) => self.buffer(token),
+                Some(token) => {
+                    self.position += 1;
+                    self.line_end = self.offset(token.slice).map(|start| start + token.slice.len());
+
+                    return Some(token);
+                },
+                None => return None,
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::Nullable;
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub enum Type {
+    Named(String),
+    Nullable(Box<Type>),
+    Union(Vec<Type>),
+    Intersection(Vec<Type>),
+    Null,
+    Static,
+    Never,
+    Mixed,
+    Void,
+}
+
+impl Type {
+    pub fn named(name: &str) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "null" => Self::Null,
+            "static" => Self::Static,
+            "never" => Self::Never,
+            "mixed" => Self::Mixed,
+            "void" => Self::Void,
+            _ => Self::Named(name.to_owned()),
+        }
+    }
+}
+
+impl Nullable for Type {
+    fn is_nullable(&self) -> bool {
+        match self {
+            Self::Nullable(..) | Self::Null | Self::Mixed => true,
+            Self::Union(types) => types.iter().any(Nullable::is_nullable),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,700 @@
+//! Builds random programs from the AST, prints them as PHP and checks that
+//! parsing the output gives back the same tree, and that printing that tree
+//! again gives back the same source.
+
+use tusk_lexer::Lexer;
+use tusk_parser::Argument;
+use tusk_parser::ArgumentList;
+use tusk_parser::ArrayItem;
+use tusk_parser::Attribute;
+use tusk_parser::AttributeGroup;
+use tusk_parser::BinaryOp;
+use tusk_parser::Class;
+use tusk_parser::ClassConst;
+use tusk_parser::ClosureType;
+use tusk_parser::ClosureUse;
+use tusk_parser::Constant;
+use tusk_parser::Expression;
+use tusk_parser::Function;
+use tusk_parser::FunctionParameter;
+use tusk_parser::ListItem;
+use tusk_parser::MagicConst;
+use tusk_parser::Parser;
+use tusk_parser::Property;
+use tusk_parser::Statement;
+use tusk_parser::Type;
+use tusk_parser::{Else, If};
+use tusk_parser::{Flag, Flaggable};
+
+const VARIABLES: &[&str] = &["a", "b", "item", "value"];
+const NAMES: &[&str] = &["foo", "bar", "count", "handle"];
+const CLASSES: &[&str] = &["Foo", "Bar", "Route", "Inject"];
+const CONSTANTS: &[&str] = &["LIMIT", "SIZE", "NAME"];
+const TYPES: &[&str] = &["int", "string", "Foo", "array"];
+const WORDS: &[&str] = &["hello", "world", "x", ""];
+
+const MAGIC_CONSTANTS: &[MagicConst] = &[
+    MagicConst::Line,
+    MagicConst::File,
+    MagicConst::Dir,
+    MagicConst::Class,
+    MagicConst::Function,
+    MagicConst::Method,
+    MagicConst::Namespace,
+    MagicConst::Trait,
+];
+
+const OPERATORS: &[BinaryOp] = &[
+    BinaryOp::Add,
+    BinaryOp::Subtract,
+    BinaryOp::Multiply,
+    BinaryOp::Divide,
+    BinaryOp::Modulo,
+    BinaryOp::BitwiseAnd,
+    BinaryOp::BitwiseOr,
+    BinaryOp::BitwiseXor,
+    BinaryOp::BitwiseLeftShift,
+    BinaryOp::BitwiseRightShift,
+    BinaryOp::And,
+    BinaryOp::Or,
+    BinaryOp::LessThan,
+    BinaryOp::GreaterThan,
+    BinaryOp::LessThanEquals,
+    BinaryOp::GreaterThanEquals,
+];
+
+/// A xorshift generator, so that every run checks the same programs.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+
+        (self.0 % n as u64) as usize
+    }
+
+    fn chance(&mut self) -> bool {
+        self.below(2) == 0
+    }
+
+    fn pick<T: Clone>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())].clone()
+    }
+
+    /// Picks up to `n` distinct items, keeping their order.
+    fn distinct(&mut self, items: &[&str], n: usize) -> Vec<String> {
+        items.iter().filter(|_| self.below(items.len()) < n).map(|item| item.to_string()).collect()
+    }
+
+    fn variable(&mut self) -> Expression {
+        Expression::Variable(self.pick(VARIABLES).to_owned())
+    }
+
+    fn name(&mut self) -> Expression {
+        Expression::Identifier(self.pick(NAMES).to_owned())
+    }
+
+    fn atom(&mut self) -> Expression {
+        match self.below(7) {
+            0 => self.variable(),
+            1 => Expression::integer(self.below(1000) as i64),
+            2 => Expression::String(self.pick(WORDS).to_owned()),
+            3 => Expression::True,
+            4 => Expression::False,
+            5 => Expression::MagicConst(self.pick(MAGIC_CONSTANTS)),
+            _ => Expression::Null,
+        }
+    }
+
+    fn expression(&mut self, depth: usize) -> Expression {
+        if depth == 0 {
+            return self.atom();
+        }
+
+        let depth = depth - 1;
+
+        match self.below(19) {
+            0 => Expression::Binary(Box::new(self.expression(depth)), self.pick(OPERATORS), Box::new(self.expression(depth))),
+            1 => Expression::Concat(Box::new(self.expression(depth)), Box::new(self.expression(depth))),
+            2 => Expression::Unary(Box::new(self.expression(depth))),
+            3 => Expression::Negate(Box::new(self.expression(depth))),
+            4 => Expression::BitwiseNot(Box::new(self.expression(depth))),
+            5 => Expression::Array(
+                (0..self.below(4))
+                    .map(|_| {
+                        let key = if self.chance() { Some(self.atom()) } else { None };
+
+                        ArrayItem::new(key, self.expression(depth), false)
+                    })
+                    .collect(),
+            ),
+            6 => Expression::Call {
+                target: Box::new(self.name()),
+                args: self.arguments(depth).into(),
+            },
+            7 => Expression::MethodCall {
+                target: Box::new(self.variable()),
+                method: Box::new(self.name()),
+                args: self.arguments(depth).into(),
+            },
+            8 => Expression::PropertyAccess(Box::new(self.variable()), Box::new(self.name())),
+            9 => Expression::ArrayAccess(Box::new(self.variable()), Some(Box::new(self.expression(depth)))),
+            10 => Expression::NullsafePropertyAccess(Box::new(self.variable()), Box::new(self.name())),
+            11 => Expression::NullsafeMethodCall {
+                target: Box::new(self.variable()),
+                method: Box::new(self.name()),
+                args: self.arguments(depth),
+            },
+            12 if self.chance() => Expression::Call {
+                target: Box::new(self.name()),
+                args: ArgumentList::FirstClassCallable,
+            },
+            12 => Expression::MethodCall {
+                target: Box::new(self.variable()),
+                method: Box::new(self.name()),
+                args: ArgumentList::FirstClassCallable,
+            },
+            13 => Expression::Closure(Box::new(self.closure(depth))),
+            14 => Expression::New {
+                class: Box::new(Expression::AnonymousClass(Box::new(self.class(depth)))),
+                args: self.arguments(depth),
+            },
+            15 => Expression::New {
+                class: Box::new(Expression::Identifier(self.pick(CLASSES).to_owned())),
+                args: self.arguments(depth),
+            },
+            16 => Expression::Yield {
+                key: if self.chance() { Some(Box::new(self.expression(depth))) } else { None },
+                value: Some(Box::new(self.expression(depth))),
+            },
+            17 => Expression::Yield { key: None, value: None },
+            _ => self.atom(),
+        }
+    }
+
+    /// Positional arguments, any of which may be spread, followed by named ones.
+    fn arguments(&mut self, depth: usize) -> Vec<Argument> {
+        let mut args: Vec<Argument> = (0..self.below(3))
+            .map(|_| match self.below(4) {
+                0 => Argument::from(Expression::Spread(Box::new(self.variable()))),
+                _ => Argument::from(self.expression(depth)),
+            })
+            .collect();
+
+        for name in self.distinct(NAMES, 1) {
+            args.push(Argument::new(Some(name), self.expression(depth)));
+        }
+
+        args
+    }
+
+    fn attributes(&mut self, depth: usize) -> Vec<AttributeGroup> {
+        if self.below(3) > 0 {
+            return Vec::new();
+        }
+
+        (0..1 + self.below(2))
+            .map(|_| AttributeGroup::new(self.distinct(CLASSES, 2).into_iter().map(|name| Attribute::new(name, self.arguments(depth))).collect()))
+            .filter(|group| !group.attributes.is_empty())
+            .collect()
+    }
+
+    fn type_hint(&mut self) -> Type {
+        match self.below(4) {
+            0 => Type::Nullable(Box::new(Type::named(self.pick(TYPES)))),
+            1 => Type::Union(vec![Type::named("int"), Type::named("string")]),
+            _ => Type::named(self.pick(TYPES)),
+        }
+    }
+
+    fn parameters(&mut self, depth: usize, promoted: bool) -> Vec<FunctionParameter> {
+        let names = self.distinct(VARIABLES, 2);
+        let count = names.len();
+
+        names
+            .into_iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let variadic = !promoted && index + 1 == count && self.below(4) == 0;
+                let type_hint = if self.chance() { Some(self.type_hint()) } else { None };
+                let default = if !variadic && self.below(3) == 0 { Some(self.atom()) } else { None };
+
+                let mut parameter = FunctionParameter::new(name, type_hint, default);
+
+                parameter.variadic = variadic;
+                parameter.by_ref = self.below(4) == 0;
+                parameter.attributes = self.attributes(depth);
+
+                if promoted && self.chance() {
+                    parameter.add_flag(self.pick(&[Flag::Public, Flag::Protected, Flag::Private]));
+
+                    if parameter.type_hint.is_some() && self.chance() {
+                        parameter.add_flag(Flag::Readonly);
+                    }
+                }
+
+                parameter
+            })
+            .collect()
+    }
+
+    fn function(&mut self, name: Option<String>, depth: usize, promoted: bool) -> Function {
+        let parameters = self.parameters(depth, promoted);
+        let return_type_hint = if self.below(3) == 0 { Some(self.type_hint()) } else { None };
+        let body = (0..self.below(3)).map(|_| self.simple_statement(depth)).collect();
+
+        let mut function = Function::new(name, parameters, Some(body), return_type_hint, Vec::new(), None);
+
+        function.attributes = self.attributes(depth);
+
+        function
+    }
+
+    fn closure(&mut self, depth: usize) -> Function {
+        let mut closure = self.function(None, depth, false);
+
+        closure.closure_type = Some(ClosureType::Long);
+        closure.uses = self
+            .distinct(VARIABLES, 2)
+            .into_iter()
+            .filter(|name| closure.parameters.iter().all(|parameter| parameter.name != *name))
+            .map(|name| ClosureUse::new(name, self.chance()))
+            .collect();
+
+        closure
+    }
+
+    fn class(&mut self, depth: usize) -> Class {
+        let mut body = Vec::new();
+
+        for name in self.distinct(CONSTANTS, 2) {
+            let type_hint = if self.below(3) == 0 { Some(self.type_hint()) } else { None };
+            let flags = if self.chance() { vec![Flag::Public] } else { Vec::new() };
+
+            let mut constant = ClassConst::new(vec![Constant::new(name, self.expression(depth))], type_hint, flags);
+
+            constant.attributes = self.attributes(depth);
+            body.push(Statement::ClassConst(constant));
+        }
+
+        let properties = self.distinct(VARIABLES, 2);
+        let constructor = if self.chance() { Some(self.function(Some("__construct".to_owned()), depth, true)) } else { None };
+
+        for name in properties {
+            if constructor.iter().flat_map(|constructor| &constructor.parameters).any(|parameter| parameter.is_promoted() && parameter.name == name) {
+                continue;
+            }
+
+            body.push(Statement::Property(Property::new(name, vec![Flag::Public], None, None)));
+        }
+
+        if let Some(mut constructor) = constructor {
+            constructor.add_flag(Flag::Public);
+            body.push(Statement::Function(constructor));
+        }
+
+        for name in self.distinct(NAMES, 2) {
+            let mut method = self.function(Some(name), depth, false);
+
+            method.add_flag(Flag::Public);
+            body.push(Statement::Function(method));
+        }
+
+        let mut class = Class::new(None, Vec::new(), String::new(), body, Vec::new());
+
+        class.attributes = self.attributes(depth);
+
+        class
+    }
+
+    fn block(&mut self, depth: usize) -> Vec<Statement> {
+        (0..self.below(4)).map(|_| self.statement(depth)).collect()
+    }
+
+    /// A statement without nested blocks, whose expressions are at most `depth` deep.
+    fn simple_statement(&mut self, depth: usize) -> Statement {
+        match self.below(8) {
+            0 => Statement::Expression(Expression::Assign(Box::new(self.variable()), Box::new(self.expression(depth)))),
+            1 => Statement::Echo(self.expression(depth)),
+            2 => Statement::Return(if self.chance() { Some(self.expression(depth)) } else { None }),
+            3 => Statement::Expression(Expression::Assign(Box::new(self.list(2)), Box::new(self.expression(depth)))),
+            4 => Statement::Goto(self.pick(NAMES).to_owned()),
+            5 => Statement::Label(self.pick(NAMES).to_owned()),
+            _ => Statement::Expression(self.expression(depth)),
+        }
+    }
+
+    /// A destructuring pattern. Skipped slots can't come last, as a trailing
+    /// comma is not a slot.
+    fn list(&mut self, depth: usize) -> Expression {
+        let keyed = self.chance();
+        let count = 1 + self.below(3);
+
+        Expression::List(
+            (0..count)
+                .map(|index| {
+                    if index + 1 < count && self.below(4) == 0 {
+                        return None;
+                    }
+
+                    let key = if keyed { Some(Expression::String(self.pick(WORDS).to_owned())) } else { None };
+                    let value = if depth > 0 && self.below(4) == 0 { self.list(depth - 1) } else { self.variable() };
+
+                    Some(ListItem::new(key, value, false))
+                })
+                .collect(),
+        )
+    }
+
+    fn statement(&mut self, depth: usize) -> Statement {
+        if depth == 0 || self.chance() {
+            return self.simple_statement(3);
+        }
+
+        match self.below(4) {
+            0 => {
+                let else_ifs = (0..self.below(3)).map(|_| Statement::ElseIf(If::new(self.expression(2), self.block(depth - 1), Vec::new(), None))).collect();
+                let r#else = if self.chance() { Some(Box::new(Statement::Else(Else::new(self.block(depth - 1))))) } else { None };
+
+                Statement::If(If::new(self.expression(2), self.block(depth - 1), else_ifs, r#else))
+            },
+            1 => Statement::While {
+                condition: self.expression(2),
+                body: self.block(depth - 1),
+            },
+            2 => Statement::Declare {
+                directives: vec![Constant::new(self.pick(&["strict_types", "ticks"]).to_owned(), Expression::integer(1))],
+                body: self.block(depth - 1),
+            },
+            _ => Statement::Foreach {
+                expression: self.variable(),
+                key_var: if self.chance() { Some(self.variable()) } else { None },
+                value_var: if self.below(3) == 0 { self.list(1) } else { self.variable() },
+                by_ref: false,
+                body: self.block(depth - 1),
+            },
+        }
+    }
+
+    fn program(&mut self) -> Vec<Statement> {
+        let mut names = NAMES.iter();
+
+        (0..1 + self.below(5))
+            .map(|_| match names.next() {
+                Some(name) if self.below(4) == 0 => Statement::Function(self.function(Some(name.to_string()), 2, false)),
+                _ => self.statement(2),
+            })
+            .collect()
+    }
+}
+
+fn print_operator(op: &BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Add => "+",
+        BinaryOp::Subtract => "-",
+        BinaryOp::Multiply => "*",
+        BinaryOp::Divide => "/",
+        BinaryOp::Modulo => "%",
+        BinaryOp::BitwiseAnd => "&",
+        BinaryOp::BitwiseOr => "|",
+        BinaryOp::BitwiseXor => "^",
+        BinaryOp::BitwiseLeftShift => "<<",
+        BinaryOp::BitwiseRightShift => ">>",
+        BinaryOp::And => "&&",
+        BinaryOp::Or => "||",
+        BinaryOp::LessThan => "<",
+        BinaryOp::GreaterThan => ">",
+        BinaryOp::LessThanEquals => "<=",
+        BinaryOp::GreaterThanEquals => ">=",
+    }
+}
+
+fn print_magic_const(magic_const: &MagicConst) -> &'static str {
+    match magic_const {
+        MagicConst::Line => "__LINE__",
+        MagicConst::File => "__FILE__",
+        MagicConst::Dir => "__DIR__",
+        MagicConst::Class => "__CLASS__",
+        MagicConst::Function => "__FUNCTION__",
+        MagicConst::Method => "__METHOD__",
+        MagicConst::Namespace => "__NAMESPACE__",
+        MagicConst::Trait => "__TRAIT__",
+    }
+}
+
+fn print_type(type_hint: &Type) -> String {
+    match type_hint {
+        Type::Named(name) => name.clone(),
+        Type::Nullable(inner) => format!("?{}", print_type(inner)),
+        Type::Union(types) => types.iter().map(print_type).collect::<Vec<_>>().join("|"),
+        other => panic!("the generator never builds {:?}", other),
+    }
+}
+
+fn print_arguments(args: &[Argument]) -> String {
+    args.iter()
+        .map(|arg| match &arg.name {
+            Some(name) => format!("{}: {}", name, print_expression(&arg.value)),
+            None => print_expression(&arg.value),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn print_argument_list(args: &ArgumentList) -> String {
+    match args {
+        ArgumentList::Arguments(args) => print_arguments(args),
+        ArgumentList::FirstClassCallable => "...".to_owned(),
+    }
+}
+
+fn print_attributes(groups: &[AttributeGroup]) -> String {
+    groups
+        .iter()
+        .map(|group| {
+            let attributes: Vec<String> = group
+                .attributes
+                .iter()
+                .map(|attribute| match attribute.args.is_empty() {
+                    true => attribute.name.clone(),
+                    false => format!("{}({})", attribute.name, print_arguments(&attribute.args)),
+                })
+                .collect();
+
+            format!("#[{}] ", attributes.join(", "))
+        })
+        .collect()
+}
+
+fn print_parameter(parameter: &FunctionParameter) -> String {
+    let flags: String = parameter
+        .flags
+        .iter()
+        .map(|flag| match flag {
+            Flag::Public => "public ",
+            Flag::Protected => "protected ",
+            Flag::Private => "private ",
+            Flag::Readonly => "readonly ",
+            other => panic!("the generator never builds {:?}", other),
+        })
+        .collect();
+
+    let type_hint = parameter.type_hint.as_ref().map(|type_hint| format!("{} ", print_type(type_hint))).unwrap_or_default();
+    let default = parameter.default.as_ref().map(|default| format!(" = {}", print_expression(default))).unwrap_or_default();
+
+    format!(
+        "{}{}{}{}{}${}{}",
+        print_attributes(&parameter.attributes),
+        flags,
+        type_hint,
+        if parameter.by_ref { "&" } else { "" },
+        if parameter.variadic { "..." } else { "" },
+        parameter.name,
+        default
+    )
+}
+
+/// Prints everything after the `function` keyword and the name.
+fn print_signature(function: &Function, indent: usize) -> String {
+    let parameters: Vec<String> = function.parameters.iter().map(print_parameter).collect();
+
+    let uses = match function.uses.is_empty() {
+        true => String::new(),
+        false => {
+            let uses: Vec<String> = function.uses.iter().map(|closure_use| format!("{}${}", if closure_use.by_ref { "&" } else { "" }, closure_use.name)).collect();
+
+            format!(" use ({})", uses.join(", "))
+        },
+    };
+
+    let return_type = function.return_type_hint.as_ref().map(|type_hint| format!(": {}", print_type(type_hint))).unwrap_or_default();
+
+    format!("({}){}{} {}", parameters.join(", "), uses, return_type, print_block(function.body.as_deref().unwrap_or_default(), indent))
+}
+
+fn print_class_body(class: &Class, indent: usize) -> String {
+    let members: Vec<String> = class
+        .body()
+        .iter()
+        .map(|member| {
+            let code = match member {
+                Statement::ClassConst(constant) => {
+                    let constants: Vec<String> = constant.constants.iter().map(|constant| format!("{} = {}", constant.name, print_expression(&constant.value))).collect();
+                    let type_hint = constant.type_hint.as_ref().map(|type_hint| format!("{} ", print_type(type_hint))).unwrap_or_default();
+
+                    format!(
+                        "{}{}const {}{};",
+                        print_attributes(&constant.attributes),
+                        if constant.has_flag(Flag::Public) { "public " } else { "" },
+                        type_hint,
+                        constants.join(", ")
+                    )
+                },
+                Statement::Property(property) => format!("public ${};", property.name),
+                Statement::Function(method) => format!(
+                    "{}public function {}{}",
+                    print_attributes(&method.attributes),
+                    method.name.as_deref().unwrap_or_default(),
+                    print_signature(method, indent + 1)
+                ),
+                other => panic!("the generator never builds {:?}", other),
+            };
+
+            format!("{}{}\n", "    ".repeat(indent + 1), code)
+        })
+        .collect();
+
+    match members.is_empty() {
+        true => "{}".to_owned(),
+        false => format!("{{\n{}{}}}", members.concat(), "    ".repeat(indent)),
+    }
+}
+
+fn print_list(items: &[Option<ListItem>]) -> String {
+    let items: Vec<String> = items
+        .iter()
+        .map(|item| match item {
+            Some(ListItem { key: Some(key), value, .. }) => format!("{} => {}", print_expression(key), print_expression(value)),
+            Some(ListItem { value, .. }) => print_expression(value),
+            None => String::new(),
+        })
+        .collect();
+
+    format!("[{}]", items.join(", "))
+}
+
+/// Prints an expression, wrapping every operator in parentheses so that
+/// precedence never changes the shape of the tree.
+fn print_expression(expression: &Expression) -> String {
+    match expression {
+        Expression::True => "true".to_owned(),
+        Expression::False => "false".to_owned(),
+        Expression::Null => "null".to_owned(),
+        Expression::String(string) => format!("'{}'", string),
+        Expression::Integer { raw, .. } => raw.clone(),
+        Expression::Variable(name) => format!("${}", name),
+        Expression::Identifier(name) => name.clone(),
+        Expression::MagicConst(magic_const) => print_magic_const(magic_const).to_owned(),
+        Expression::Binary(lhs, op, rhs) => format!("({} {} {})", print_expression(lhs), print_operator(op), print_expression(rhs)),
+        Expression::Concat(lhs, rhs) => format!("({} . {})", print_expression(lhs), print_expression(rhs)),
+        Expression::Assign(lhs, rhs) => format!("{} = {}", print_expression(lhs), print_expression(rhs)),
+        Expression::Unary(rhs) => format!("(-{})", print_expression(rhs)),
+        Expression::Negate(rhs) => format!("(!{})", print_expression(rhs)),
+        Expression::BitwiseNot(rhs) => format!("(~{})", print_expression(rhs)),
+        Expression::Spread(value) => format!("...{}", print_expression(value)),
+        Expression::Array(items) => {
+            let items: Vec<String> = items
+                .iter()
+                .map(|item| match &item.key {
+                    Some(key) => format!("{} => {}", print_expression(key), print_expression(&item.value)),
+                    None => print_expression(&item.value),
+                })
+                .collect();
+
+            format!("[{}]", items.join(", "))
+        },
+        Expression::List(items) => print_list(items),
+        Expression::Call { target, args } => format!("{}({})", print_expression(target), print_argument_list(args)),
+        Expression::MethodCall { target, method, args } => format!("{}->{}({})", print_expression(target), print_expression(method), print_argument_list(args)),
+        Expression::NullsafeMethodCall { target, method, args } => format!("{}?->{}({})", print_expression(target), print_expression(method), print_arguments(args)),
+        Expression::PropertyAccess(target, property) => format!("{}->{}", print_expression(target), print_expression(property)),
+        Expression::NullsafePropertyAccess(target, property) => format!("{}?->{}", print_expression(target), print_expression(property)),
+        Expression::ArrayAccess(target, Some(index)) => format!("{}[{}]", print_expression(target), print_expression(index)),
+        // a closure at the start of a statement would read as a function declaration
+        Expression::Closure(function) => format!("({}function {})", print_attributes(&function.attributes), print_signature(function, 0)),
+        Expression::New { class, args } => match class.as_ref() {
+            Expression::AnonymousClass(class) => format!("(new {}class({}) {})", print_attributes(&class.attributes), print_arguments(args), print_class_body(class, 0)),
+            class => format!("(new {}({}))", print_expression(class), print_arguments(args)),
+        },
+        Expression::Yield { key, value } => match (key, value) {
+            (Some(key), Some(value)) => format!("(yield {} => {})", print_expression(key), print_expression(value)),
+            (None, Some(value)) => format!("(yield {})", print_expression(value)),
+            _ => "(yield)".to_owned(),
+        },
+        other => panic!("the generator never builds {:?}", other),
+    }
+}
+
+fn print_block(statements: &[Statement], indent: usize) -> String {
+    if statements.is_empty() {
+        return "{}".to_owned();
+    }
+
+    let inner: String = statements.iter().map(|statement| print_statement(statement, indent + 1)).collect();
+
+    format!("{{\n{}{}}}", inner, "    ".repeat(indent))
+}
+
+fn print_statement(statement: &Statement, indent: usize) -> String {
+    let code = match statement {
+        Statement::Expression(expression) => format!("{};", print_expression(expression)),
+        Statement::Echo(expression) => format!("echo {};", print_expression(expression)),
+        Statement::Return(Some(expression)) => format!("return {};", print_expression(expression)),
+        Statement::Return(None) => "return;".to_owned(),
+        Statement::Goto(label) => format!("goto {};", label),
+        Statement::Label(label) => format!("{}:", label),
+        Statement::If(r#if) => {
+            let mut code = format!("if ({}) {}", print_expression(r#if.condition()), print_block(r#if.then(), indent));
+
+            for else_if in r#if.else_ifs() {
+                if let Statement::ElseIf(else_if) = else_if {
+                    code.push_str(&format!(" elseif ({}) {}", print_expression(else_if.condition()), print_block(else_if.then(), indent)));
+                }
+            }
+
+            if let Some(Statement::Else(r#else)) = r#if.r#else() {
+                code.push_str(&format!(" else {}", print_block(r#else.then(), indent)));
+            }
+
+            code
+        },
+        Statement::While { condition, body } => format!("while ({}) {}", print_expression(condition), print_block(body, indent)),
+        Statement::Declare { directives, body } => {
+            let directives: Vec<String> = directives.iter().map(|directive| format!("{}={}", directive.name, print_expression(&directive.value))).collect();
+
+            match body.is_empty() {
+                true => format!("declare({});", directives.join(", ")),
+                false => format!("declare({}) {}", directives.join(", "), print_block(body, indent)),
+            }
+        },
+        Statement::Foreach { expression, key_var, value_var, body, .. } => {
+            let key = key_var.as_ref().map(|key| format!("{} => ", print_expression(key))).unwrap_or_default();
+
+            format!("foreach ({} as {}{}) {}", print_expression(expression), key, print_expression(value_var), print_block(body, indent))
+        },
+        Statement::Function(function) => format!(
+            "{}function {}{}",
+            print_attributes(&function.attributes),
+            function.name.as_deref().unwrap_or_default(),
+            print_signature(function, indent)
+        ),
+        other => panic!("the generator never builds {:?}", other),
+    };
+
+    format!("{}{}\n", "    ".repeat(indent), code)
+}
+
+fn print_program(program: &[Statement]) -> String {
+    program.iter().map(|statement| print_statement(statement, 0)).collect()
+}
+
+#[test]
+fn test_printed_programs_parse_back_to_the_same_tree() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+
+    for _ in 0..500 {
+        let program = rng.program();
+        let source = print_program(&program);
+
+        let parsed = Parser::new(Lexer::new(&source)).all().unwrap_or_else(|error| panic!("{}\n\n{}", error, source));
+
+        assert_eq!(parsed, program, "\n{}", source);
+
+        let reprinted = print_program(&parsed);
+
+        assert_eq!(Parser::new(Lexer::new(&reprinted)).all().unwrap(), parsed);
+        assert_eq!(reprinted, source);
+    }
+}
@@ -1,7 +1,36 @@
-use tusk_lexer::Lexer;
+use tusk_lexer::{Lexer, TokenType};
+use tusk_parser::find_constants;
+use tusk_parser::Argument;
+use tusk_parser::ArgumentList;
+use tusk_parser::ArrayItem;
+use tusk_parser::Attribute;
+use tusk_parser::BinaryOp;
+use tusk_parser::AttributeGroup;
+use tusk_parser::Class;
+use tusk_parser::Comment;
+use tusk_parser::ClosureType;
+use tusk_parser::ClosureUse;
+use tusk_parser::ClassConst;
+use tusk_parser::Constant;
+use tusk_parser::DocBlock;
+use tusk_parser::DocType;
+use tusk_parser::Flag;
+use tusk_parser::Flaggable;
 use tusk_parser::Expression;
+use tusk_parser::Function;
+use tusk_parser::FunctionParameter;
+use tusk_parser::Interface;
+use tusk_parser::MagicConst;
+use tusk_parser::ListItem;
+use tusk_parser::Nullable;
 use tusk_parser::Parser;
+use tusk_parser::ParserError;
+use tusk_parser::Property;
+use tusk_parser::ShapeField;
 use tusk_parser::Statement;
+use tusk_parser::StaticVar;
+use tusk_parser::Tag;
+use tusk_parser::Type;
 
 #[test]
 fn test_it_can_be_created() {
@@ -13,12 +42,975 @@ fn test_it_can_be_created() {
 #[test]
 fn test_it_can_parse_literals() {
     assert_statements_match("12345 12345.6789 'Hello, world!'", vec![
-        Statement::Expression(Expression::Integer(12345)),
-        Statement::Expression(Expression::Float(12345.6789)),
+        Statement::Expression(Expression::integer(12345)),
+        Statement::Expression(Expression::float(12345.6789)),
         Statement::Expression(Expression::String("Hello, world!".to_owned())),
     ]);
 }
 
+#[test]
+fn test_it_can_parse_method_calls() {
+    assert_statements_match("$user->save(true);", vec![
+        Statement::Expression(Expression::MethodCall {
+            target: Box::new(Expression::Variable("user".to_owned())),
+            method: Box::new(Expression::Identifier("save".to_owned())),
+            args: ArgumentList::Arguments(vec![Argument::from(Expression::True)]),
+        }),
+    ]);
+}
+
+#[test]
+fn test_it_can_parse_nullsafe_and_dynamic_member_access() {
+    assert_statements_match("$user?->$field; $user?->{$method}();", vec![
+        Statement::Expression(Expression::NullsafePropertyAccess(
+            Box::new(Expression::Variable("user".to_owned())),
+            Box::new(Expression::Variable("field".to_owned())),
+        )),
+        Statement::Expression(Expression::NullsafeMethodCall {
+            target: Box::new(Expression::Variable("user".to_owned())),
+            method: Box::new(Expression::Variable("method".to_owned())),
+            args: Vec::new(),
+        }),
+    ]);
+}
+
+#[test]
+fn test_it_can_parse_variadic_and_by_reference_parameters() {
+    let mut by_ref = FunctionParameter::new("items".to_owned(), Some(Type::Named("array".to_owned())), None);
+    by_ref.by_ref = true;
+
+    let mut variadic = FunctionParameter::new("rest".to_owned(), None, None);
+    variadic.variadic = true;
+
+    assert_statements_match("function push(array &$items, ...$rest) {}", vec![
+        Statement::Function(Function::new(Some("push".to_owned()), vec![by_ref, variadic], Some(Vec::new()), None, Vec::new(), None)),
+    ]);
+}
+
+#[test]
+fn test_it_rejects_variadic_parameters_that_are_not_last() {
+    let lexer = Lexer::new("function push(...$rest, $item) {}");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::VariadicParameterMustBeLast(name)) if name == "rest"));
+}
+
+#[test]
+fn test_it_can_parse_spread_and_by_reference_arguments() {
+    assert_statements_match("sort(&$items, ...$flags);", vec![
+        Statement::Expression(Expression::Call {
+            target: Box::new(Expression::Identifier("sort".to_owned())),
+            args: ArgumentList::Arguments(vec![
+                Argument::from(Expression::Reference(Box::new(Expression::Variable("items".to_owned())))),
+                Argument::from(Expression::Spread(Box::new(Expression::Variable("flags".to_owned())))),
+            ]),
+        }),
+    ]);
+}
+
+#[test]
+fn test_it_can_parse_named_arguments() {
+    assert_statements_match("new Query($db, limit: 10);", vec![
+        Statement::Expression(Expression::New {
+            class: Box::new(Expression::Identifier("Query".to_owned())),
+            args: vec![
+                Argument::from(Expression::Variable("db".to_owned())),
+                Argument::new(Some("limit".to_owned()), Expression::integer(10)),
+            ],
+        }),
+    ]);
+}
+
+#[test]
+fn test_it_rejects_invalid_named_arguments() {
+    let lexer = Lexer::new("find(limit: 10, $id);");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::PositionalArgumentAfterNamedArgument)));
+
+    let lexer = Lexer::new("$db->find(limit: 10, limit: 20);");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::DuplicateNamedArgument(name)) if name == "limit"));
+}
+
+#[test]
+fn test_it_can_parse_structured_types() {
+    let parameters = vec![
+        FunctionParameter::new("id".to_owned(), Some(Type::Nullable(Box::new(Type::Named("int".to_owned())))), None),
+        FunctionParameter::new("key".to_owned(), Some(Type::Union(vec![Type::Named("int".to_owned()), Type::Named("string".to_owned())])), None),
+    ];
+
+    let return_type = Type::Union(vec![
+        Type::Intersection(vec![Type::Named("Countable".to_owned()), Type::Named("Iterator".to_owned())]),
+        Type::Null,
+    ]);
+
+    assert_statements_match("function find(?int $id, int|string $key): (Countable&Iterator)|null {}", vec![
+        Statement::Function(Function::new(Some("find".to_owned()), parameters, Some(Vec::new()), Some(return_type), Vec::new(), None)),
+    ]);
+}
+
+#[test]
+fn test_it_can_parse_typed_properties() {
+    let count = Property::new("count".to_owned(), vec![Flag::Public], Some(Type::Union(vec![Type::Named("int".to_owned()), Type::Null])), Some(Expression::integer(0)));
+
+    assert!(count.is_nullable());
+
+    assert_statements_match("class Counter { public int|null $count = 0; }", vec![
+        Statement::Class(Class::new(Some("Counter".to_owned()), Vec::new(), String::new(), vec![Statement::Property(count)], Vec::new())),
+    ]);
+}
+
+#[test]
+fn test_it_can_parse_promoted_constructor_parameters() {
+    let mut id = FunctionParameter::new("id".to_owned(), Some(Type::Named("int".to_owned())), None);
+    id.flags = vec![Flag::Private, Flag::Readonly];
+
+    let mut constructor = Function::new(Some("__construct".to_owned()), vec![id], Some(Vec::new()), None, Vec::new(), None);
+    constructor.add_flag(Flag::Public);
+
+    assert_statements_match("readonly class User { public function __construct(private readonly int $id) {} }", vec![
+        Statement::Class(Class::new(Some("User".to_owned()), Vec::new(), String::new(), vec![Statement::Function(constructor)], vec![Flag::Readonly])),
+    ]);
+}
+
+#[test]
+fn test_it_rejects_invalid_promoted_and_readonly_properties() {
+    let lexer = Lexer::new("function create(private int $id) {}");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::PromotedPropertyOutsideConstructor(name)) if name == "id"));
+
+    let lexer = Lexer::new("interface Entity { public function __construct(private int $id); }");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::PromotedPropertyInAbstractConstructor(name)) if name == "id"));
+
+    let lexer = Lexer::new("abstract class Entity { abstract public function __construct(private int $id); }");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::PromotedPropertyInAbstractConstructor(name)) if name == "id"));
+
+    let lexer = Lexer::new("class User { public readonly $id; }");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::ReadonlyPropertyWithoutType(name)) if name == "id"));
+
+    let lexer = Lexer::new("readonly class User { public function __construct(public $id) {} }");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::ReadonlyPropertyWithoutType(name)) if name == "id"));
+
+    let lexer = Lexer::new("class User { public $id; public function __construct(public $id) {} }");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::PropertyAlreadyExists(name)) if name == "id"));
+
+    let lexer = Lexer::new("function __construct(private $id) {}");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::PromotedPropertyOutsideConstructor(name)) if name == "id"));
+}
+
+#[test]
+fn test_it_can_parse_class_constants() {
+    let limits = ClassConst::new(
+        vec![Constant::new("MIN".to_owned(), Expression::integer(1)), Constant::new("MAX".to_owned(), Expression::integer(10))],
+        Some(Type::Named("int".to_owned())),
+        vec![Flag::Public, Flag::Final],
+    );
+
+    assert_statements_match("class Page { final public const int MIN = 1, MAX = 10; }", vec![
+        Statement::Class(Class::new(Some("Page".to_owned()), Vec::new(), String::new(), vec![Statement::ClassConst(limits)], Vec::new())),
+    ]);
+}
+
+#[test]
+fn test_it_rejects_duplicate_class_constants() {
+    let lexer = Lexer::new("class Page { const SIZE = 1; private const SIZE = 2; }");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::ConstantAlreadyExists(name)) if name == "SIZE"));
+}
+
+#[test]
+fn test_it_rejects_class_constants_outside_classes() {
+    for source in ["public const X = 1;", "if (true) { public const X = 1; }", "if (true) { const int X = 1; }", "function f() { #[A] const X = 1; }"] {
+        let lexer = Lexer::new(source);
+        let mut parser = Parser::new(lexer);
+
+        assert!(matches!(parser.all(), Err(ParserError::UnexpectedStatement(statement)) if matches!(*statement, Statement::ClassConst(..))), "{}", source);
+    }
+}
+
+#[test]
+fn test_it_only_allows_constants_at_the_top_level() {
+    assert_statements_match("namespace App\\Models; const X = 1;", vec![
+        Statement::Namespace(Expression::Identifier("App\\Models".to_owned())),
+        Statement::Const(vec![Constant::new("X".to_owned(), Expression::integer(1))]),
+    ]);
+
+    for source in ["function f() { const X = 1; }", "if (true) { const X = 1; }", "if (true) { namespace App; }"] {
+        let lexer = Lexer::new(source);
+        let mut parser = Parser::new(lexer);
+
+        assert!(matches!(parser.all(), Err(ParserError::UnexpectedStatement(statement)) if matches!(*statement, Statement::Const(..) | Statement::Namespace(..))), "{}", source);
+    }
+}
+
+#[test]
+fn test_it_can_parse_methods_without_bodies() {
+    let mut handle = Function::new(Some("handle".to_owned()), Vec::new(), None, Some(Type::Void), Vec::new(), None);
+    handle.add_flag(Flag::Public);
+    handle.add_flag(Flag::Abstract);
+
+    let mut job = Class::new(Some("Job".to_owned()), Vec::new(), String::new(), vec![Statement::Function(handle.clone())], Vec::new());
+    job.add_flag(Flag::Abstract);
+
+    let mut contract = Function::new(Some("handle".to_owned()), Vec::new(), None, Some(Type::Void), Vec::new(), None);
+    contract.add_flag(Flag::Public);
+
+    assert_statements_match("abstract class Job { abstract public function handle(): void; } interface Handler { public function handle(): void; }", vec![
+        Statement::Class(job),
+        Statement::Interface(Interface::new("Handler".to_owned(), Vec::new(), vec![Statement::Function(contract)])),
+    ]);
+}
+
+#[test]
+fn test_it_validates_abstract_methods() {
+    let lexer = Lexer::new("class Job { abstract public function handle(); }");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::ClassMustBeAbstract(name)) if name == "Job"));
+
+    let lexer = Lexer::new("abstract class Job { abstract public function handle() {} }");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::AbstractMethodWithBody(name)) if name == "handle"));
+
+    let lexer = Lexer::new("class Job { public function handle(); }");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::MethodWithoutBody(name)) if name == "handle"));
+
+    let lexer = Lexer::new("if (true) { class Job { abstract function handle(); } }");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::ClassMustBeAbstract(name)) if name == "Job"));
+
+    let lexer = Lexer::new("if (true) { function handle(); }");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::MethodWithoutBody(name)) if name == "handle"));
+}
+
+#[test]
+fn test_it_can_find_const_and_define_constants() {
+    let lexer = Lexer::new("const FOO = 1, BAR = 2; define('BAZ', 3); if (true) { define('QUX', 4); }");
+    let mut parser = Parser::new(lexer);
+    let program = parser.all().unwrap();
+
+    assert_eq!(program[0], Statement::Const(vec![
+        Constant::new("FOO".to_owned(), Expression::integer(1)),
+        Constant::new("BAR".to_owned(), Expression::integer(2)),
+    ]));
+
+    let names: Vec<String> = find_constants(&program).into_iter().map(|constant| constant.name).collect();
+
+    assert_eq!(names, vec!["FOO", "BAR", "BAZ", "QUX"]);
+
+    let lexer = Lexer::new("if (defined('FOO')) {} elseif (define('FOO', 1)) {} else { define('BAR', 2); }");
+    let mut parser = Parser::new(lexer);
+    let program = parser.all().unwrap();
+
+    let names: Vec<String> = find_constants(&program).into_iter().map(|constant| constant.name).collect();
+
+    assert_eq!(names, vec!["FOO", "BAR"]);
+
+    let lexer = Lexer::new("defined('FOO') || define('FOO', 1); function boot() { define('BAR', 2); } class A { public function boot() { define('BAZ', 3); } }");
+    let mut parser = Parser::new(lexer);
+    let program = parser.all().unwrap();
+
+    let names: Vec<String> = find_constants(&program).into_iter().map(|constant| constant.name).collect();
+
+    assert_eq!(names, vec!["FOO", "BAR", "BAZ"]);
+}
+
+#[test]
+fn test_it_can_parse_anonymous_classes() {
+    let mut id = Property::new("id".to_owned(), Vec::new(), Some(Type::Named("int".to_owned())), None);
+    id.add_flag(Flag::Public);
+
+    let mut class = Class::new(None, vec!["Jsonable".to_owned()], "Model".to_owned(), vec![Statement::Property(id)], Vec::new());
+    class.add_flag(Flag::Readonly);
+
+    assert_statements_match("new readonly class($attributes) extends Model implements Jsonable { public int $id; };", vec![
+        Statement::Expression(Expression::New {
+            class: Box::new(Expression::AnonymousClass(Box::new(class))),
+            args: vec![Argument::from(Expression::Variable("attributes".to_owned()))],
+        }),
+    ]);
+
+    let lexer = Lexer::new("new #[A] Foo;");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::ExpectedToken { expected_type: TokenType::Class, got_slice: "Foo", .. })));
+}
+
+#[test]
+fn test_it_can_parse_closure_use_clauses() {
+    let mut closure = Function::new(None, Vec::new(), Some(Vec::new()), None, Vec::new(), Some(ClosureType::Long));
+    closure.uses = vec![ClosureUse::new("total".to_owned(), false), ClosureUse::new("count".to_owned(), true)];
+
+    assert_statements_match("$increment = function () use ($total, &$count) {};", vec![
+        Statement::Expression(Expression::Assign(Box::new(Expression::Variable("increment".to_owned())), Box::new(Expression::Closure(Box::new(closure))))),
+    ]);
+}
+
+#[test]
+fn test_it_rejects_invalid_closure_use_clauses() {
+    let lexer = Lexer::new("$f = function () use ($total, &$total) {};");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::DuplicateClosureUse(name)) if name == "total"));
+
+    let lexer = Lexer::new("$f = function ($total) use ($total) {};");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::ClosureUseShadowsParameter(name)) if name == "total"));
+
+    for source in ["$f = function () use ($a $b) {};", "$f = function () use ($a,,$b) {};", "$f = function () use () {};"] {
+        let mut parser = Parser::new(Lexer::new(source));
+
+        assert!(matches!(parser.all(), Err(ParserError::UnexpectedToken(..))), "{}", source);
+    }
+}
+
+#[test]
+fn test_it_can_parse_by_reference_functions_and_assignments() {
+    let mut function = Function::new(Some("getRef".to_owned()), Vec::new(), Some(Vec::new()), None, Vec::new(), None);
+    function.by_ref = true;
+
+    let mut closure = Function::new(
+        None,
+        vec![FunctionParameter::new("x".to_owned(), None, None)],
+        Some(vec![Statement::Expression(Expression::Variable("x".to_owned()))]),
+        None,
+        Vec::new(),
+        Some(ClosureType::Short),
+    );
+    closure.by_ref = true;
+
+    assert_statements_match("function &getRef() {} $a = &$b; $id = fn&($x) => $x;", vec![
+        Statement::Function(function),
+        Statement::Expression(Expression::AssignRef(Box::new(Expression::Variable("a".to_owned())), Box::new(Expression::Variable("b".to_owned())))),
+        Statement::Expression(Expression::Assign(Box::new(Expression::Variable("id".to_owned())), Box::new(Expression::Closure(Box::new(closure))))),
+    ]);
+}
+
+#[test]
+fn test_it_can_parse_foreach_by_reference() {
+    assert_statements_match("foreach ($items as $key => &$item) {}", vec![
+        Statement::Foreach {
+            expression: Expression::Variable("items".to_owned()),
+            key_var: Some(Expression::Variable("key".to_owned())),
+            value_var: Expression::Variable("item".to_owned()),
+            by_ref: true,
+            body: Vec::new(),
+        },
+    ]);
+}
+
+#[test]
+fn test_it_can_parse_yield_expressions() {
+    assert_statements_match("yield; $received = yield $id => $row; yield from $rows;", vec![
+        Statement::Expression(Expression::Yield { key: None, value: None }),
+        Statement::Expression(Expression::Assign(
+            Box::new(Expression::Variable("received".to_owned())),
+            Box::new(Expression::Yield {
+                key: Some(Box::new(Expression::Variable("id".to_owned()))),
+                value: Some(Box::new(Expression::Variable("row".to_owned()))),
+            }),
+        )),
+        Statement::Expression(Expression::YieldFrom(Box::new(Expression::Variable("rows".to_owned())))),
+    ]);
+
+    assert_statements_match("$a = yield $b = 1;", vec![
+        Statement::Expression(Expression::Assign(
+            Box::new(Expression::Variable("a".to_owned())),
+            Box::new(Expression::Yield {
+                key: None,
+                value: Some(Box::new(Expression::Assign(
+                    Box::new(Expression::Variable("b".to_owned())),
+                    Box::new(Expression::integer(1)),
+                ))),
+            }),
+        )),
+    ]);
+}
+
+#[test]
+fn test_it_can_detect_generators() {
+    let lexer = Lexer::new("function rows() { while (true) { yield 1; } } function map() { return function () { yield 1; }; }");
+    let mut parser = Parser::new(lexer);
+    let program = parser.all().unwrap();
+
+    assert!(matches!(&program[0], Statement::Function(function) if function.is_generator()));
+    assert!(matches!(&program[1], Statement::Function(function) if !function.is_generator()));
+
+    let lexer = Lexer::new("function rows($x) { if (false) {} elseif (yield $x) {} }");
+    let mut parser = Parser::new(lexer);
+    let program = parser.all().unwrap();
+
+    assert!(matches!(&program[0], Statement::Function(function) if function.is_generator()));
+}
+
+#[test]
+fn test_it_can_parse_attributes() {
+    let mut foo = Property::new("foo".to_owned(), Vec::new(), Some(Type::Named("Foo".to_owned())), None);
+    foo.add_flag(Flag::Private);
+    foo.attributes = vec![AttributeGroup::new(vec![Attribute::new("Inject".to_owned(), Vec::new())])];
+
+    let mut id = FunctionParameter::new("id".to_owned(), Some(Type::Named("int".to_owned())), None);
+    id.attributes = vec![AttributeGroup::new(vec![Attribute::new("FromRoute".to_owned(), Vec::new())])];
+
+    let mut show = Function::new(Some("show".to_owned()), vec![id], Some(Vec::new()), None, Vec::new(), None);
+    show.add_flag(Flag::Public);
+    show.attributes = vec![
+        AttributeGroup::new(vec![Attribute::new("Get".to_owned(), Vec::new())]),
+        AttributeGroup::new(vec![
+            Attribute::new("Route".to_owned(), vec![
+                Argument::from(Expression::String("/users/{id}".to_owned())),
+                Argument::new(Some("name".to_owned()), Expression::String("users.show".to_owned())),
+            ]),
+            Attribute::new("Cached".to_owned(), Vec::new()),
+        ]),
+    ];
+
+    let mut controller = Class::new(Some("UserController".to_owned()), Vec::new(), String::new(), vec![Statement::Property(foo), Statement::Function(show)], Vec::new());
+    controller.attributes = vec![AttributeGroup::new(vec![Attribute::new("Controller".to_owned(), Vec::new())])];
+
+    assert_statements_match(
+        "#[Controller] class UserController { #[Inject] private Foo $foo; #[Get] #[Route('/users/{id}', name: 'users.show'), Cached] public function show(#[FromRoute] int $id) {} }",
+        vec![Statement::Class(controller)],
+    );
+}
+
+#[test]
+fn test_it_attaches_comments_to_declarations() {
+    let mut bar = Function::new(Some("bar".to_owned()), Vec::new(), Some(vec![Statement::Expression(Expression::Assign(Box::new(Expression::Variable("a".to_owned())), Box::new(Expression::integer(1))))]), None, Vec::new(), None);
+    bar.add_flag(Flag::Public);
+    bar.doc_comment = Some("/** Does bar. */".to_owned());
+    bar.trivia.leading = vec![Comment::DocBlock("/** Does bar. */".to_owned()), Comment::Line("// keep public".to_owned())];
+    bar.trivia.trailing = vec![Comment::Block("/* end */".to_owned())];
+
+    let mut baz = Property::new("baz".to_owned(), vec![Flag::Private], None, None);
+    baz.trivia.leading = vec![Comment::Line("# state".to_owned())];
+
+    let mut foo = Class::new(Some("Foo".to_owned()), Vec::new(), String::new(), vec![Statement::Property(baz), Statement::Function(bar)], Vec::new());
+    foo.doc_comment = Some("/** A foo. */".to_owned());
+    foo.trivia.leading = vec![Comment::DocBlock("/** A foo. */".to_owned())];
+
+    let mut parser = Parser::new(Lexer::new(
+        "<?php /** A foo. */ class Foo { # state
+        private $baz; /** Does bar. */ // keep public
+        public function bar() { $a = /* kept */ 1; } /* end */ }",
+    ));
+
+    assert_eq!(parser.all().unwrap(), vec![Statement::OpenTag, Statement::Class(foo)]);
+    assert_eq!(parser.comments().cloned().collect::<Vec<_>>(), vec![Comment::Block("/* kept */".to_owned())]);
+}
+
+#[test]
+fn test_it_keeps_comments_around_other_statements() {
+    let mut foo = Function::new(Some("foo".to_owned()), Vec::new(), Some(Vec::new()), None, Vec::new(), None);
+    foo.doc_comment = Some("/** Doc */".to_owned());
+    foo.trivia.leading = vec![Comment::DocBlock("/** Doc */".to_owned())];
+    foo.trivia.trailing = vec![Comment::Line("// end".to_owned())];
+
+    let mut parser = Parser::new(Lexer::new("// start\n$a = foo(/* arg */ 1); # note\nif ($a) {} /** Doc */ function foo() {} // end"));
+
+    assert_eq!(parser.all().unwrap()[2], Statement::Function(foo));
+    assert_eq!(
+        parser.comments().cloned().collect::<Vec<_>>(),
+        vec![
+            Comment::Line("// start".to_owned()),
+            Comment::Block("/* arg */".to_owned()),
+            Comment::Line("# note".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn test_it_attaches_same_line_comments_to_the_declaration_before_them() {
+    let mut a = Property::new("a".to_owned(), vec![Flag::Public], None, None);
+    a.trivia.trailing = vec![Comment::Line("// note".to_owned())];
+
+    let mut b = Property::new("b".to_owned(), vec![Flag::Public], None, None);
+    b.trivia.leading = vec![Comment::Line("// about b".to_owned())];
+
+    let c = Property::new("c".to_owned(), vec![Flag::Public], None, None);
+
+    let program = Parser::from_source("<?php class A {\n    public $a; // note\n    // about b\n    public $b; /** c */\n    public $c;\n}").all().unwrap();
+
+    let mut c_documented = c.clone();
+    c_documented.doc_comment = Some("/** c */".to_owned());
+    c_documented.trivia.leading = vec![Comment::DocBlock("/** c */".to_owned())];
+
+    assert_eq!(
+        program[1],
+        Statement::Class(Class::new(Some("A".to_owned()), Vec::new(), String::new(), vec![Statement::Property(a), Statement::Property(b), Statement::Property(c_documented)], Vec::new()))
+    );
+}
+
+#[test]
+fn test_it_can_parse_doc_blocks() {
+    let doc = DocBlock::parse("/**
+     * Finds users by id.
+     *
+     * Results are cached for a minute.
+     * Missing ids are skipped.
+     *
+     * @template T of Model
+     * @param array<int, string> $ids The ids
+     *                                to look up.
+     * @param array{id: int, name?: string} &$filter
+     * @param callable(T, int): bool ...$callbacks
+     * @return list<T>|null
+     * @throws \\RuntimeException When the cache is down.
+     * @deprecated Use find() instead.
+     * @property-read int $count
+     */");
+
+    let named = |name: &str| DocType::Named(name.to_owned());
+
+    assert_eq!(doc.summary, "Finds users by id.");
+    assert_eq!(doc.description, "Results are cached for a minute.\nMissing ids are skipped.");
+    assert_eq!(doc.tags, vec![
+        Tag::Template { name: "T".to_owned(), bound: Some(named("Model")), description: String::new() },
+        Tag::Param {
+            type_hint: Some(DocType::Generic { name: "array".to_owned(), args: vec![named("int"), named("string")] }),
+            name: "ids".to_owned(),
+            description: "The ids\nto look up.".to_owned(),
+        },
+        Tag::Param {
+            type_hint: Some(DocType::Shape {
+                name: "array".to_owned(),
+                fields: vec![
+                    ShapeField { key: Some("id".to_owned()), optional: false, value: named("int") },
+                    ShapeField { key: Some("name".to_owned()), optional: true, value: named("string") },
+                ],
+            }),
+            name: "filter".to_owned(),
+            description: String::new(),
+        },
+        Tag::Param {
+            type_hint: Some(DocType::Callable { name: "callable".to_owned(), parameters: vec![named("T"), named("int")], return_type: Some(Box::new(named("bool"))) }),
+            name: "callbacks".to_owned(),
+            description: String::new(),
+        },
+        Tag::Return {
+            type_hint: DocType::Union(vec![DocType::Generic { name: "list".to_owned(), args: vec![named("T")] }, named("null")]),
+            description: String::new(),
+        },
+        Tag::Throws { type_hint: named("\\RuntimeException"), description: "When the cache is down.".to_owned() },
+        Tag::Deprecated { description: "Use find() instead.".to_owned() },
+        Tag::Other { name: "property-read".to_owned(), body: "int $count".to_owned() },
+    ]);
+}
+
+#[test]
+fn test_it_can_parse_doc_types() {
+    let named = |name: &str| DocType::Named(name.to_owned());
+
+    assert_eq!(DocType::parse("?Foo[]"), Some(DocType::Nullable(Box::new(DocType::Array(Box::new(named("Foo")))))));
+    assert_eq!(DocType::parse("(A&B)|'none'"), Some(DocType::Union(vec![DocType::Intersection(vec![named("A"), named("B")]), DocType::Literal("'none'".to_owned())])));
+    assert_eq!(DocType::parse("array<int"), None);
+
+    let doc = DocBlock::parse("/** @var int $count How many. */");
+    assert_eq!(doc.summary, "");
+    assert_eq!(doc.tags, vec![Tag::Var { type_hint: named("int"), name: Some("count".to_owned()), description: "How many.".to_owned() }]);
+
+    let doc = DocBlock::parse("/** @property Closure(): void $callback */");
+    assert_eq!(doc.tags, vec![Tag::Property {
+        type_hint: DocType::Callable { name: "Closure".to_owned(), parameters: Vec::new(), return_type: Some(Box::new(named("void"))) },
+        name: "callback".to_owned(),
+        description: String::new(),
+    }]);
+}
+
+#[test]
+fn test_it_can_parse_destructuring() {
+    let var = |name: &str| Expression::Variable(name.to_owned());
+    let item = |value: Expression| Some(ListItem::new(None, value, false));
+
+    assert_statements_match(
+        "[$a, , &$b] = $pair; list(, $second) = $pair;",
+        vec![
+            Statement::Expression(Expression::Assign(
+                Box::new(Expression::List(vec![item(var("a")), None, Some(ListItem::new(None, var("b"), true))])),
+                Box::new(var("pair")),
+            )),
+            Statement::Expression(Expression::Assign(
+                Box::new(Expression::List(vec![None, item(var("second"))])),
+                Box::new(var("pair")),
+            )),
+        ],
+    );
+
+    assert_statements_match(
+        "['id' => $id, 'tags' => [$first]] = $row; foreach ($rows as $k => [$x, list($y)]) {}",
+        vec![
+            Statement::Expression(Expression::Assign(
+                Box::new(Expression::List(vec![
+                    Some(ListItem::new(Some(Expression::String("id".to_owned())), var("id"), false)),
+                    Some(ListItem::new(Some(Expression::String("tags".to_owned())), Expression::List(vec![item(var("first"))]), false)),
+                ])),
+                Box::new(var("row")),
+            )),
+            Statement::Foreach {
+                expression: var("rows"),
+                key_var: Some(var("k")),
+                value_var: Expression::List(vec![item(var("x")), item(Expression::List(vec![item(var("y"))]))]),
+                by_ref: false,
+                body: Vec::new(),
+            },
+        ],
+    );
+
+    assert_statements_match(
+        "$pair = [$a, $b];",
+        vec![Statement::Expression(Expression::Assign(
+            Box::new(var("pair")),
+            Box::new(Expression::Array(vec![ArrayItem::new(None, var("a"), false), ArrayItem::new(None, var("b"), false)])),
+        ))],
+    );
+}
+
+#[test]
+fn test_it_rejects_invalid_list_patterns() {
+    for source in ["[] = $x;", "list() = $x;", "[, ,] = $x;", "[$a, []] = $x;"] {
+        let lexer = Lexer::new(source);
+        let mut parser = Parser::new(lexer);
+
+        assert!(matches!(parser.all(), Err(ParserError::EmptyList)), "{}", source);
+    }
+
+    let lexer = Lexer::new("foreach ($a as &[$x]) {}");
+    let mut parser = Parser::new(lexer);
+
+    assert!(matches!(parser.all(), Err(ParserError::ListByReference)));
+}
+
+#[test]
+fn test_it_can_parse_array_items() {
+    let var = |name: &str| Expression::Variable(name.to_owned());
+    let assign = |array: Vec<ArrayItem>| Statement::Expression(Expression::Assign(Box::new(var("a")), Box::new(Expression::Array(array))));
+
+    assert_statements_match(
+        "$a = [1, 2]; $a = [0 => 1, '5' => 2]; $a = array(...$b, &$c, 'd' => &$d,);",
+        vec![
+            assign(vec![ArrayItem::new(None, Expression::integer(1), false), ArrayItem::new(None, Expression::integer(2), false)]),
+            assign(vec![
+                ArrayItem::new(Some(Expression::integer(0)), Expression::integer(1), false),
+                ArrayItem::new(Some(Expression::String("5".to_owned())), Expression::integer(2), false),
+            ]),
+            assign(vec![
+                ArrayItem::spread(var("b")),
+                ArrayItem::new(None, var("c"), true),
+                ArrayItem::new(Some(Expression::String("d".to_owned())), var("d"), true),
+            ]),
+        ],
+    );
+
+    assert!(matches!(
+        Parser::new(Lexer::new("$a = [1, , 2];")).all(),
+        Err(ParserError::UnexpectedToken(..))
+    ));
+}
+
+#[test]
+fn test_it_can_parse_language_constructs() {
+    let var = |name: &str| Expression::Variable(name.to_owned());
+    let string = |value: &str| Expression::String(value.to_owned());
+    let expression = |expression: Expression| Statement::Expression(expression);
+
+    assert_statements_match(
+        "isset($a, $b[0],); empty($a); unset($a, $b); exit; die('bye'); exit(); eval('1;');",
+        vec![
+            expression(Expression::Isset(vec![var("a"), Expression::ArrayAccess(Box::new(var("b")), Some(Box::new(Expression::integer(0))))])),
+            expression(Expression::Empty(Box::new(var("a")))),
+            Statement::Unset(vec![var("a"), var("b")]),
+            expression(Expression::Exit(None)),
+            expression(Expression::Exit(Some(Box::new(string("bye"))))),
+            expression(Expression::Exit(None)),
+            expression(Expression::Eval(Box::new(string("1;")))),
+        ],
+    );
+
+    assert_statements_match(
+        "$b = clone $a->b + 1; print $c = 1; require_once $dir . 'a.php'; include 'b.php'; include_once 'c.php'; require 'd.php';",
+        vec![
+            expression(Expression::Assign(
+                Box::new(var("b")),
+                Box::new(Expression::Binary(
+                    Box::new(Expression::Clone(Box::new(Expression::PropertyAccess(Box::new(var("a")), Box::new(Expression::Identifier("b".to_owned())))))),
+                    BinaryOp::Add,
+                    Box::new(Expression::integer(1)),
+                )),
+            )),
+            expression(Expression::Print(Box::new(Expression::Assign(Box::new(var("c")), Box::new(Expression::integer(1)))))),
+            expression(Expression::RequireOnce(Box::new(Expression::Concat(Box::new(var("dir")), Box::new(string("a.php")))))),
+            expression(Expression::Include(Box::new(string("b.php")))),
+            expression(Expression::IncludeOnce(Box::new(string("c.php")))),
+            expression(Expression::Require(Box::new(string("d.php")))),
+        ],
+    );
+
+    assert!(matches!(Parser::new(Lexer::new("isset();")).all(), Err(ParserError::UnexpectedToken(TokenType::RightParen, ..))));
+}
+
+#[test]
+fn test_it_can_parse_globals_static_vars_and_error_suppression() {
+    let counter = Function::new(Some("counter".to_owned()), Vec::new(), Some(vec![
+        Statement::Global(vec!["wpdb".to_owned(), "post".to_owned()]),
+        Statement::StaticVar(vec![StaticVar::new("count".to_owned(), Some(Expression::integer(0))), StaticVar::new("cache".to_owned(), None)]),
+        Statement::Expression(Expression::ErrorSuppress(Box::new(Expression::Call {
+            target: Box::new(Expression::Identifier("unlink".to_owned())),
+            args: ArgumentList::Arguments(vec![Argument::from(Expression::Variable("file".to_owned()))]),
+        }))),
+    ]), None, Vec::new(), None);
+
+    assert_statements_match(
+        "function counter() { global $wpdb, $post; static $count = 0, $cache; @unlink($file); }",
+        vec![Statement::Function(counter)],
+    );
+
+    let instance = Property::new("instance".to_owned(), vec![Flag::Static, Flag::Public], None, None);
+    let count = Property::new("count".to_owned(), vec![Flag::Static], None, Some(Expression::integer(0)));
+
+    assert_statements_match(
+        "class Foo { public static $instance; static $count = 0; }",
+        vec![Statement::Class(Class::new(Some("Foo".to_owned()), Vec::new(), String::new(), vec![Statement::Property(instance), Statement::Property(count)], Vec::new()))],
+    );
+}
+
+#[test]
+fn test_it_can_parse_declare_goto_halt_compiler_and_bare_returns() {
+    let strict_types = || vec![Constant::new("strict_types".to_owned(), Expression::integer(1))];
+
+    assert_statements_match(
+        "declare(strict_types=1); declare(strict_types=1) { echo 1; } declare(strict_types=1): echo 1; enddeclare; declare(strict_types=1) echo 1;",
+        vec![
+            Statement::Declare { directives: strict_types(), body: Vec::new() },
+            Statement::Declare { directives: strict_types(), body: vec![Statement::Echo(Expression::integer(1))] },
+            Statement::Declare { directives: strict_types(), body: vec![Statement::Echo(Expression::integer(1))] },
+            Statement::Declare { directives: strict_types(), body: vec![Statement::Echo(Expression::integer(1))] },
+        ],
+    );
+
+    assert_statements_match(
+        "while ($a) { break 2; continue; } retry: goto retry; return; return 1;",
+        vec![
+            Statement::While {
+                condition: Expression::Variable("a".to_owned()),
+                body: vec![Statement::Break(Some(Expression::integer(2))), Statement::Continue(None)],
+            },
+            Statement::Label("retry".to_owned()),
+            Statement::Goto("retry".to_owned()),
+            Statement::Return(None),
+            Statement::Return(Some(Expression::integer(1))),
+        ],
+    );
+
+    let mut parser = Parser::from_source("<?php echo 1; __halt_compiler();\x01raw } data");
+
+    assert_eq!(parser.all().unwrap(), vec![
+        Statement::OpenTag,
+        Statement::Echo(Expression::integer(1)),
+        Statement::HaltCompiler(Some("\x01raw } data".to_owned())),
+    ]);
+}
+
+#[test]
+fn test_it_can_parse_magic_constants_and_first_class_callables() {
+    assert_statements_match(
+        "echo __DIR__ . __line__; echo __CLASS__;",
+        vec![
+            Statement::Echo(Expression::Concat(Box::new(Expression::MagicConst(MagicConst::Dir)), Box::new(Expression::MagicConst(MagicConst::Line)))),
+            Statement::Echo(Expression::MagicConst(MagicConst::Class)),
+        ],
+    );
+
+    assert_statements_match(
+        "$a = strlen(...); $b = $container->get(...);",
+        vec![
+            Statement::Expression(Expression::Assign(
+                Box::new(Expression::Variable("a".to_owned())),
+                Box::new(Expression::Call { target: Box::new(Expression::Identifier("strlen".to_owned())), args: ArgumentList::FirstClassCallable }),
+            )),
+            Statement::Expression(Expression::Assign(
+                Box::new(Expression::Variable("b".to_owned())),
+                Box::new(Expression::MethodCall {
+                    target: Box::new(Expression::Variable("container".to_owned())),
+                    method: Box::new(Expression::Identifier("get".to_owned())),
+                    args: ArgumentList::FirstClassCallable,
+                }),
+            )),
+        ],
+    );
+
+    assert!(matches!(Parser::new(Lexer::new("$a?->b(...);")).all(), Err(ParserError::UnexpectedToken(TokenType::Ellipsis, ..))));
+}
+
+#[test]
+fn test_it_can_parse_integer_literal_forms() {
+    let integer = |value: i64, raw: &str| Statement::Expression(Expression::Integer { value, raw: raw.to_owned() });
+    let float = |value: f64, raw: &str| Statement::Expression(Expression::Float { value, raw: raw.to_owned() });
+
+    assert_statements_match(
+        "0x1F 0XFF 0b1010 0o17 017 0_17 0 1_000_000 1_000.5 9223372036854775807 9223372036854775808 0xFFFFFFFFFFFFFFFF",
+        vec![
+            integer(31, "0x1F"),
+            integer(255, "0XFF"),
+            integer(10, "0b1010"),
+            integer(15, "0o17"),
+            integer(15, "017"),
+            integer(15, "0_17"),
+            integer(0, "0"),
+            integer(1_000_000, "1_000_000"),
+            float(1000.5, "1_000.5"),
+            integer(i64::MAX, "9223372036854775807"),
+            float(9223372036854775808.0, "9223372036854775808"),
+            float(18446744073709551615.0, "0xFFFFFFFFFFFFFFFF"),
+        ],
+    );
+
+    assert!(matches!(Parser::new(Lexer::new("$a = 089;")).all(), Err(ParserError::IntegerParserError)));
+
+    for source in ["$a = 1__0;", "$a = 1_;", "$a = 0x_1F;", "$a = 0b_1;"] {
+        assert!(matches!(Parser::new(Lexer::new(source)).all(), Err(ParserError::IntegerParserError)), "{}", source);
+    }
+
+    assert!(matches!(Parser::new(Lexer::new("$a = 1_.5;")).all(), Err(ParserError::FloatParserError)));
+}
+
+#[test]
+fn test_it_attaches_comments_to_constants() {
+    let mut foo = Constant::new("FOO".to_owned(), Expression::integer(1));
+    foo.doc_comment = Some("/** Doc */".to_owned());
+    foo.trivia.leading = vec![Comment::DocBlock("/** Doc */".to_owned())];
+
+    assert_statements_match(
+        "/** Doc */ const FOO = 1, BAR = 2;",
+        vec![Statement::Const(vec![foo, Constant::new("BAR".to_owned(), Expression::integer(2))])],
+    );
+
+    let program = Parser::from_source("<?php class A {\n    public const X = 1; // note\n    const Y = 2;\n}").all().unwrap();
+
+    let mut x = ClassConst::new(vec![Constant::new("X".to_owned(), Expression::integer(1))], None, Vec::new());
+    x.add_flag(Flag::Public);
+    x.trivia.trailing = vec![Comment::Line("// note".to_owned())];
+
+    let y = ClassConst::new(vec![Constant::new("Y".to_owned(), Expression::integer(2))], None, Vec::new());
+
+    assert_eq!(program[1], Statement::Class(Class::new(Some("A".to_owned()), Vec::new(), String::new(), vec![Statement::ClassConst(x), Statement::ClassConst(y)], Vec::new())));
+}
+
+#[test]
+fn test_it_rejects_malformed_attribute_groups() {
+    for source in ["#[] function foo() {}", "#[A B] function foo() {}", "#[A,,B] function foo() {}", "#[A,] , function foo() {}"] {
+        let mut parser = Parser::new(Lexer::new(source));
+
+        assert!(matches!(parser.all(), Err(ParserError::UnexpectedToken(..))), "{}", source);
+    }
+
+    let mut function = Function::new(Some("foo".to_owned()), Vec::new(), Some(Vec::new()), None, Vec::new(), None);
+    function.attributes = vec![AttributeGroup::new(vec![Attribute::new("A".to_owned(), Vec::new()), Attribute::new("B".to_owned(), Vec::new())])];
+
+    assert_statements_match("#[A, B,] function foo() {}", vec![Statement::Function(function)]);
+}
+
+#[test]
+fn test_it_shares_one_signature_parser() {
+    let program = Parser::new(Lexer::new("$f = fn (#[Foo] int &...$rest,) => $rest;")).all().unwrap();
+    let parameters = match &program[0] {
+        Statement::Expression(Expression::Assign(_, value)) => match value.as_ref() {
+            Expression::Closure(function) => function.parameters.clone(),
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+
+    assert_eq!(parameters.len(), 1);
+    assert!(parameters[0].by_ref && parameters[0].variadic);
+    assert_eq!(parameters[0].attributes.len(), 1);
+
+    assert!(matches!(Parser::new(Lexer::new("$f = function (public $a) {};")).all(), Err(ParserError::PromotedPropertyOutsideConstructor(_))));
+    assert!(matches!(Parser::new(Lexer::new("$f = function (...$a, $b) {};")).all(), Err(ParserError::VariadicParameterMustBeLast(_))));
+    assert!(matches!(Parser::new(Lexer::new("function foo($a,, $b) {}")).all(), Err(ParserError::ExpectedToken { got_type: TokenType::Comma, .. })));
+}
+
+#[test]
+fn test_it_never_panics_on_malformed_input() {
+    let seeds = [
+        "<?php namespace App; use Foo\\Bar; #[Attr] final class A extends B implements C { public function __construct(private readonly int $a = 1) {} }",
+        "function &foo(int|string $a, ?Foo &...$b): static { static $c = 0; global $d; return fn ($e) => $e?->f(...)[0]; }",
+        "$a = [1, 'b' => &$c, ...$d]; [$e, [, $f]] = $a; list('g' => $h) = $i; foreach ($a as $k => [$l, $m]) { yield $k => $l; }",
+        "if ($a) { echo 1; } elseif ($b) { print @$c; } else { exit(1); } while (true) { break 2; } declare(strict_types=1); goto end; end:",
+        "$a = new class(1) extends B { const C = 1; }; $b = function () use (&$a) { return clone $a->b::$c; }; __halt_compiler(); raw",
+        "interface A extends B { public function c(): void; } abstract class D { abstract protected function e(); }",
+        "/** @param array{a: int, b?: list<string>} $a */ function f($a) { return isset($a['a']) && !empty($a) ? 0x1F : 0b1_0; }",
+    ];
+
+    let fragments = [
+        "$a", "1", "'b'", "(", ")", "[", "]", "{", "}", ";", ",", "=", "=>", "->", "?->", "::", "+", "-", "!", "~", "@", "&", "...",
+        "?", ":", "function", "fn", "class", "new", "static", "public", "const", "use", "if", "else", "foreach", "as", "yield",
+        "list", "array", "isset", "clone", "print", "include", "return", "break", "declare", "goto", "#[", "/** @var int */", "// c",
+    ];
+
+    for seed in seeds {
+        for (end, _) in seed.char_indices() {
+            let _ = Parser::new(Lexer::new(&seed[..end])).all();
+            let _ = Parser::new(Lexer::new(&seed[end..])).all();
+        }
+    }
+
+    // a small xorshift generator keeps the corpus the same on every run
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+
+    for _ in 0..2_000 {
+        let mut source = String::new();
+
+        for _ in 0..(state % 24) {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+
+            source.push_str(fragments[(state % fragments.len() as u64) as usize]);
+            source.push(' ');
+        }
+
+        let _ = Parser::new(Lexer::new(&source)).all();
+    }
+
+    let nested = [
+        "(".repeat(10_000),
+        "if ($a) {".repeat(10_000),
+        "[".repeat(10_000),
+        "-".repeat(10_000),
+        "foo(".repeat(10_000),
+        "$a->b(".repeat(10_000),
+        "$f = function () {".repeat(10_000),
+        "new class { public function a() {".repeat(10_000),
+    ];
+
+    for source in nested {
+        assert!(matches!(Parser::new(Lexer::new(&source)).all(), Err(ParserError::MaximumDepthExceeded(_))));
+    }
+}
+
 fn assert_statements_match(source: &str, statements: Vec<Statement>) {
     let lexer = Lexer::new(source);
     let mut parser = Parser::new(lexer);